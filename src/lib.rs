@@ -1,4 +1,15 @@
-#![allow(dead_code)]
+pub mod memory;
+pub mod processor;
 
-mod memory;
-mod processor;
+pub use memory::Memory;
+pub use memory::address::{Address, AddressMode, AddressModeKind, ZeroPageAddress};
+pub use memory::banked::{Banked, ChipId, WindowId};
+pub use memory::bus::{Bus, Device, DeviceId, WritePolicy};
+pub use memory::ram::Ram;
+pub use memory::rom::Rom;
+pub use memory::vec_memory::VecMemory;
+pub use processor::{DecimalResult, ExecutionError, Instruction, Processor, Registers, RunState, UnstablePolicy, Value};
+pub use processor::cmos::{Cmos, CmosProcessor};
+pub use processor::nmos::{Nmos, NmosProcessor};
+pub use processor::status::Status;
+pub use processor::variant::Variant;
@@ -1,7 +1,8 @@
 use std::ops::Add;
+use crate::memory::Memory;
 use crate::processor::Value;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ZeroPageAddress(pub u8);
 
 impl ZeroPageAddress {
@@ -16,7 +17,7 @@ impl ZeroPageAddress {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Address(pub u16);
 
 impl Address {
@@ -44,9 +45,10 @@ impl Add<u8> for Address {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressMode {
     Implied,
+    Accumulator,                          // A
     Immediate(Value),
     ZeroPage(ZeroPageAddress),
     ZeroPageX(ZeroPageAddress),
@@ -57,7 +59,89 @@ pub enum AddressMode {
     Indirect(Address),                    // ($ff22)
     PreIndexedIndirectX(ZeroPageAddress),  // (Zero-Page,X)
     PostIndexedIndirectY(ZeroPageAddress), // (Zero-Page), Y
-    Relative(Address),
+    Relative(i8),
+    ZeroPageIndirect(ZeroPageAddress),     // (Zero-Page), 65C02
+    AbsoluteIndexedIndirectX(Address),     // ($ff22,X), 65C02 JMP only
+    ZeroPageRelative(ZeroPageAddress, i8), // Zero-Page, Relative, 65C02 BBR/BBS only
+}
+
+impl AddressMode {
+    // peeks at the operand bytes following the op code at the address
+    pub fn peek_operand<M: Memory>(memory: &M, op_code_address: Address, address_mode_kind: &AddressModeKind) -> Self {
+        let operand_address = op_code_address.add(1u8);
+
+        let low = memory.peek(&operand_address);
+        let high = memory.peek(&operand_address.add(1u8));
+
+        Self::from_operand(address_mode_kind, low, high)
+    }
+
+    // builds the address mode from the operand bytes in the order they follow the op code,
+    // the high byte is ignored by the modes with a single byte operand
+    pub fn from_operand(address_mode_kind: &AddressModeKind, low: u8, high: u8) -> Self {
+        let word = Address::from_bytes(low, high);
+
+        match address_mode_kind {
+            AddressModeKind::Implied => AddressMode::Implied,
+            AddressModeKind::Accumulator => AddressMode::Accumulator,
+            AddressModeKind::Immediate => AddressMode::Immediate(low),
+            AddressModeKind::ZeroPage => AddressMode::ZeroPage(ZeroPageAddress(low)),
+            AddressModeKind::ZeroPageX => AddressMode::ZeroPageX(ZeroPageAddress(low)),
+            AddressModeKind::ZeroPageY => AddressMode::ZeroPageY(ZeroPageAddress(low)),
+            AddressModeKind::Absolute => AddressMode::Absolute(word),
+            AddressModeKind::AbsoluteX => AddressMode::AbsoluteX(word),
+            AddressModeKind::AbsoluteY => AddressMode::AbsoluteY(word),
+            AddressModeKind::Indirect => AddressMode::Indirect(word),
+            AddressModeKind::PreIndexedIndirectX => AddressMode::PreIndexedIndirectX(ZeroPageAddress(low)),
+            AddressModeKind::PostIndexedIndirectY => AddressMode::PostIndexedIndirectY(ZeroPageAddress(low)),
+            AddressModeKind::Relative => AddressMode::Relative(low as i8),
+            AddressModeKind::ZeroPageIndirect => AddressMode::ZeroPageIndirect(ZeroPageAddress(low)),
+            AddressModeKind::AbsoluteIndexedIndirectX => AddressMode::AbsoluteIndexedIndirectX(word),
+            AddressModeKind::ZeroPageRelative => AddressMode::ZeroPageRelative(ZeroPageAddress(low), high as i8),
+        }
+    }
+
+    pub fn kind(&self) -> AddressModeKind {
+        match self {
+            AddressMode::Implied => AddressModeKind::Implied,
+            AddressMode::Accumulator => AddressModeKind::Accumulator,
+            AddressMode::Immediate(_) => AddressModeKind::Immediate,
+            AddressMode::ZeroPage(_) => AddressModeKind::ZeroPage,
+            AddressMode::ZeroPageX(_) => AddressModeKind::ZeroPageX,
+            AddressMode::ZeroPageY(_) => AddressModeKind::ZeroPageY,
+            AddressMode::Absolute(_) => AddressModeKind::Absolute,
+            AddressMode::AbsoluteX(_) => AddressModeKind::AbsoluteX,
+            AddressMode::AbsoluteY(_) => AddressModeKind::AbsoluteY,
+            AddressMode::Indirect(_) => AddressModeKind::Indirect,
+            AddressMode::PreIndexedIndirectX(_) => AddressModeKind::PreIndexedIndirectX,
+            AddressMode::PostIndexedIndirectY(_) => AddressModeKind::PostIndexedIndirectY,
+            AddressMode::Relative(_) => AddressModeKind::Relative,
+            AddressMode::ZeroPageIndirect(_) => AddressModeKind::ZeroPageIndirect,
+            AddressMode::AbsoluteIndexedIndirectX(_) => AddressModeKind::AbsoluteIndexedIndirectX,
+            AddressMode::ZeroPageRelative(_, _) => AddressModeKind::ZeroPageRelative,
+        }
+    }
+}
+
+// an address mode without its operand, as known after decoding an op code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressModeKind {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    PreIndexedIndirectX,
+    PostIndexedIndirectY,
+    Relative,
+    ZeroPageIndirect,
+    AbsoluteIndexedIndirectX,
+    ZeroPageRelative,
 }
 
 
@@ -82,10 +166,10 @@ mod test {
     fn test_add_page_check(){
         let (address, crossed) = Address(0x00ff).add_check_page_cross(0x0001);
         assert_eq!(address.0, 0x0100);
-        assert_eq!(crossed, true);
+        assert!(crossed);
 
         let (address, crossed) = Address(0x01fa).add_check_page_cross(0x0001);
         assert_eq!(address.0, 0x01fb);
-        assert_eq!(crossed, false);
+        assert!(!crossed);
     }
 }
\ No newline at end of file
@@ -0,0 +1,324 @@
+use std::ops::Range;
+use crate::memory::bus::Device;
+use crate::processor::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChipId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowId(usize);
+
+// the ram or rom a mapper switches banks of into its windows
+struct Chip {
+    bytes: Vec<u8>,
+    read_only: bool,
+}
+
+// one window's worth of a chip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bank {
+    chip: usize,
+    bank: usize,
+}
+
+// reads and writes can see different banks, like rom that's written through to the ram under it
+struct Window {
+    range: Range<u32>,
+    read: Bank,
+    write: Bank,
+}
+
+type Switch = Box<dyn FnMut(&mut Banked, u16, Value)>;
+
+struct Register {
+    range: Range<u32>,
+    switch: Switch,
+}
+
+// a device made of windows onto banks of its chips, switched around by writing to its
+// registers. windows and registers are at offsets into the range the device is mapped at,
+// where they overlap the one added last wins. offsets outside any window read as zero
+#[derive(Default)]
+pub struct Banked {
+    chips: Vec<Chip>,
+    windows: Vec<Window>,
+    registers: Vec<Register>,
+}
+
+impl Banked {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_ram(&mut self, size: usize) -> ChipId {
+        self.chips.push(Chip { bytes: vec![0; size], read_only: false });
+        ChipId(self.chips.len() - 1)
+    }
+
+    // writes to a rom bank are ignored
+    pub fn add_rom(&mut self, bytes: &[u8]) -> ChipId {
+        self.chips.push(Chip { bytes: bytes.to_vec(), read_only: true });
+        ChipId(self.chips.len() - 1)
+    }
+
+    // adds a window at the range showing the bank of the chip, counted in the window's size
+    pub fn add_window(&mut self, range: Range<u32>, chip: ChipId, bank: usize) -> WindowId {
+        assert!(range.start < range.end && range.end <= 0x10000, "{:#x?} isn't a range of offsets", range);
+
+        self.windows.push(Window {
+            range,
+            read: Bank { chip: 0, bank: 0 },
+            write: Bank { chip: 0, bank: 0 },
+        });
+
+        let window = WindowId(self.windows.len() - 1);
+        self.select(window, chip, bank);
+        window
+    }
+
+    // calls switch with the offset into the range and the value for every write to the range,
+    // the write goes no further. switch can add registers of its own, they go after the rest
+    pub fn add_register<F: FnMut(&mut Banked, u16, Value) + 'static>(&mut self, range: Range<u32>, switch: F) {
+        assert!(range.start < range.end && range.end <= 0x10000, "{:#x?} isn't a range of offsets", range);
+
+        self.registers.push(Register { range, switch: Box::new(switch) });
+    }
+
+    // switches the window to the bank of the chip for reads and writes. like the hardware
+    // ignoring the bank register bits it has no use for, the bank wraps around the chip
+    pub fn select(&mut self, window: WindowId, chip: ChipId, bank: usize) {
+        self.select_read(window, chip, bank);
+        self.select_write(window, chip, bank);
+    }
+
+    pub fn select_read(&mut self, window: WindowId, chip: ChipId, bank: usize) {
+        self.windows[window.0].read = self.bank(window, chip, bank);
+    }
+
+    pub fn select_write(&mut self, window: WindowId, chip: ChipId, bank: usize) {
+        self.windows[window.0].write = self.bank(window, chip, bank);
+    }
+
+    // the chip and bank the window reads from
+    pub fn selected(&self, window: WindowId) -> (ChipId, usize) {
+        let read = self.windows[window.0].read;
+        (ChipId(read.chip), read.bank)
+    }
+
+    fn bank(&self, window: WindowId, chip: ChipId, bank: usize) -> Bank {
+        let window = &self.windows[window.0];
+        let size = (window.range.end - window.range.start) as usize;
+        let banks = self.chips[chip.0].bytes.len() / size;
+        assert!(banks > 0, "{:?} is smaller than a window of {:#x} bytes", chip, size);
+
+        Bank { chip: chip.0, bank: bank % banks }
+    }
+
+    fn register(&self, offset: u16) -> Option<usize> {
+        self.registers.iter().rposition(|register| register.range.contains(&(offset as u32)))
+    }
+
+    // the chip and the index into it the offset reaches
+    fn resolve(&self, offset: u16, write: bool) -> Option<(usize, usize)> {
+        let offset = offset as u32;
+        let window = self.windows.iter().rev().find(|window| window.range.contains(&offset))?;
+
+        let bank = if write { window.write } else { window.read };
+        let size = window.range.end - window.range.start;
+        Some((bank.chip, (bank.bank * size as usize) + (offset - window.range.start) as usize))
+    }
+}
+
+impl Device for Banked {
+    fn peek(&self, offset: u16) -> Value {
+        match self.resolve(offset, false) {
+            Some((chip, index)) => self.chips[chip].bytes[index],
+            None => 0,
+        }
+    }
+
+    // up to the end of the furthest window or register
+    fn len(&self) -> usize {
+        let windows = self.windows.iter().map(|window| window.range.end);
+        let registers = self.registers.iter().map(|register| register.range.end);
+        windows.chain(registers).max().unwrap_or(0) as usize
+    }
+
+    // a rom bank is read only where it's written to, registers never are
+    fn read_only(&self, offset: u16) -> bool {
+        if self.register(offset).is_some() {
+            return false;
+        }
+
+        self.resolve(offset, true).is_some_and(|(chip, _)| self.chips[chip].read_only)
+    }
+
+    fn write(&mut self, offset: u16, value: Value) {
+        let register = self.register(offset);
+
+        if let Some(register) = register {
+            // taken out while it runs so the switch can have the rest of the device
+            let mut registers = std::mem::take(&mut self.registers);
+            let relative = offset - registers[register].range.start as u16;
+            (registers[register].switch)(self, relative, value);
+
+            let added = std::mem::replace(&mut self.registers, registers);
+            self.registers.extend(added);
+            return;
+        }
+
+        if let Some((chip, index)) = self.resolve(offset, true) {
+            let chip = &mut self.chips[chip];
+            if !chip.read_only {
+                chip.bytes[index] = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::banked::Banked;
+    use crate::memory::bus::{Bus, Device, WritePolicy};
+    use crate::memory::Memory;
+    use crate::processor::cmos::CmosProcessor;
+    use crate::processor::ExecutionError;
+
+    #[test]
+    fn test_select() {
+        let mut banked = Banked::new();
+        let rom = banked.add_rom(&[0x00, 0x01, 0x10, 0x11, 0x20, 0x21]);
+        let window = banked.add_window(0x0000..0x0002, rom, 1);
+        assert_eq!(banked.peek(0x0001), 0x11);
+
+        banked.select(window, rom, 2);
+        assert_eq!(banked.peek(0x0000), 0x20);
+
+        // the bank wraps around the chip
+        banked.select(window, rom, 3);
+        assert_eq!(banked.peek(0x0000), 0x00);
+        assert_eq!(banked.selected(window), (rom, 0));
+
+        // rom isn't written, and nothing is outside the windows
+        banked.write(0x0000, 0x42);
+        assert_eq!(banked.peek(0x0000), 0x00);
+        assert_eq!(banked.peek(0x0002), 0x00);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x0100);
+        let rom = banked.add_rom(&[0x42; 0x10]);
+        banked.add_window(0x0000..0x0100, ram, 0);
+        banked.add_window(0x0080..0x0090, rom, 0);
+
+        // the window added last wins where they overlap
+        assert_eq!(banked.peek(0x0085), 0x42);
+        banked.write(0x0090, 0x24);
+        assert_eq!(banked.peek(0x0090), 0x24);
+    }
+
+    #[test]
+    fn test_write_through() {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x0004);
+        let rom = banked.add_rom(&[0x42; 0x02]);
+        let window = banked.add_window(0x0000..0x0002, rom, 0);
+        banked.select_write(window, ram, 1);
+
+        // reads see the rom while writes go to the second half of the ram
+        banked.write(0x0001, 0x24);
+        assert_eq!(banked.peek(0x0001), 0x42);
+
+        banked.select_read(window, ram, 1);
+        assert_eq!(banked.peek(0x0001), 0x24);
+        assert_eq!(banked.peek(0x0000), 0x00);
+    }
+
+    #[test]
+    fn test_register() {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x0400);
+        let window = banked.add_window(0x0000..0x0100, ram, 0);
+        banked.add_register(0x00f0..0x0100, move |banked, offset, value| {
+            banked.select(window, ram, offset as usize + value as usize);
+        });
+
+        banked.write(0x0010, 0x42);
+
+        // the write selects the bank instead of reaching the ram below the register
+        banked.write(0x00f1, 0x01);
+        assert_eq!(banked.selected(window), (ram, 2));
+        assert_eq!(banked.peek(0x00f1), 0x00);
+        assert_eq!(banked.peek(0x0010), 0x00);
+
+        banked.write(0x00f0, 0x00);
+        assert_eq!(banked.peek(0x0010), 0x42);
+    }
+
+    #[test]
+    fn test_register_added_by_register() {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x0200);
+        let window = banked.add_window(0x0000..0x0100, ram, 0);
+
+        // the first register unlocks a second one
+        banked.add_register(0x00f0..0x00f1, move |banked, _, _| {
+            banked.add_register(0x00f1..0x00f2, move |banked, _, value| banked.select(window, ram, value as usize));
+        });
+
+        banked.write(0x00f1, 0x01);
+        assert_eq!(banked.selected(window), (ram, 0));
+
+        banked.write(0x00f0, 0x00);
+        banked.write(0x00f1, 0x01);
+        assert_eq!(banked.selected(window), (ram, 1));
+    }
+
+    // ram with a window of rom at $8000 holding STA $8000, NOP
+    fn rom_window() -> Banked {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x10000);
+        let rom = banked.add_rom(&[0x8D, 0x00, 0x80, 0xEA]);
+        banked.add_window(0x0000..0x10000, ram, 0);
+        banked.add_window(0x8000..0x8004, rom, 0);
+        banked
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut banked = Banked::new();
+        let ram = banked.add_ram(0x0100);
+        let rom = banked.add_rom(&[0x42; 0x10]);
+        banked.add_window(0x0000..0x0100, ram, 0);
+        let window = banked.add_window(0x0080..0x0090, rom, 0);
+        banked.add_register(0x0080..0x0081, |_, _, _| {});
+
+        assert!(banked.read_only(0x0081));
+        assert!(!banked.read_only(0x0090));
+        assert!(!banked.read_only(0x0080));
+
+        // a window reading rom but writing ram isn't read only
+        banked.select_write(window, ram, 0);
+        assert!(!banked.read_only(0x0081));
+    }
+
+    #[test]
+    fn test_write_policy() {
+        let mut bus = Bus::new();
+        let banked = bus.add(rom_window());
+        bus.map(0x0000..0x10000, banked);
+        bus.set_write_policy(WritePolicy::Error);
+
+        let mut processor = CmosProcessor::with_memory(bus);
+        processor.set_program_counter(0x8000);
+        processor.set_accumulator(0x42);
+
+        // the bank refuses the store like a rom of its own would
+        assert_eq!(processor.step(), Err(ExecutionError::WriteToRom { address: 0x8000, value: 0x42 }));
+        assert_eq!(processor.bus().peek(&Address(0x8000)), 0x8D);
+        assert_eq!(processor.step(), Ok(2));
+    }
+}
@@ -0,0 +1,425 @@
+use std::any::Any;
+use std::ops::Range;
+use crate::memory::address::Address;
+use crate::memory::Memory;
+use crate::processor::Value;
+
+// something that sits on the bus, addressed by the offset into the range it's mapped at
+pub trait Device: Any {
+    // what's at the offset, without the side effects of a read
+    fn peek(&self, offset: u16) -> Value;
+
+    // a read on the bus, for the devices that change when they're read
+    fn read(&mut self, offset: u16) -> Value {
+        self.peek(offset)
+    }
+
+    fn write(&mut self, offset: u16, value: Value);
+
+    // the offsets the device answers to, mapping it over more than this is refused
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // advances the device by a cycle, for the ones keeping time of their own
+    fn tick(&mut self) {}
+
+    // writes to a read only offset are handled by the bus's write policy, for the devices
+    // that are only partly read only, like rom switched in among ram
+    fn read_only(&self, _offset: u16) -> bool {
+        false
+    }
+}
+
+// what the bus does with a write to read only memory, the device never sees it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritePolicy {
+    // the write goes nowhere, like on the hardware
+    #[default]
+    Ignore,
+
+    // the write goes nowhere but is kept, to be taken with rom_writes()
+    Log,
+
+    // the write fails the processor's step with ExecutionError::WriteToRom
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceId(usize);
+
+struct Mapping {
+    range: Range<u32>,
+    // the range repeats the device every this many bytes
+    size: u32,
+    device: usize,
+}
+
+// a memory map made of devices mapped at half-open address ranges. where ranges overlap
+// the one mapped last wins, and reading an address nothing is mapped at sees whatever
+// was last on the bus
+#[derive(Default)]
+pub struct Bus {
+    devices: Vec<Box<dyn Device>>,
+    mappings: Vec<Mapping>,
+    open_bus: Value,
+    write_policy: WritePolicy,
+    rom_writes: Vec<(Address, Value)>,
+    write_fault: Option<(Address, Value)>,
+}
+
+impl Bus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // adds the device without mapping it anywhere yet, it can be mapped at any number of ranges.
+    // it's ticked every cycle whether it's mapped or not
+    pub fn add<D: Device>(&mut self, device: D) -> DeviceId {
+        self.devices.push(Box::new(device));
+        DeviceId(self.devices.len() - 1)
+    }
+
+    // maps the device at the range, the first address of the range at offset zero.
+    // the end of the range can be $10000 to reach the top of memory
+    pub fn map(&mut self, range: Range<u32>, device: DeviceId) {
+        let size = range.end.saturating_sub(range.start);
+        self.map_mirrored(range, size, device);
+    }
+
+    // maps the device at the range, repeating it every size bytes
+    pub fn map_mirrored(&mut self, range: Range<u32>, size: u32, device: DeviceId) {
+        assert!(range.start < range.end && range.end <= 0x10000, "{:#x?} isn't a range of addresses", range);
+        assert!(size > 0 && size <= 0x10000, "a device can't be mirrored every {:#x} bytes", size);
+        assert!(device.0 < self.devices.len(), "{:?} wasn't added to the bus", device);
+
+        let len = self.devices[device.0].len();
+        assert!(size as usize <= len, "{:?} is {:#x} bytes, too small to map {:#x} bytes of", device, len, size);
+
+        self.mappings.push(Mapping { range, size, device: device.0 });
+    }
+
+    pub fn set_write_policy(&mut self, write_policy: WritePolicy) {
+        self.write_policy = write_policy;
+    }
+
+    // the writes to read only devices logged since the last call
+    pub fn rom_writes(&mut self) -> Vec<(Address, Value)> {
+        std::mem::take(&mut self.rom_writes)
+    }
+
+    pub fn device<D: Device>(&self, device: DeviceId) -> Option<&D> {
+        let device: &dyn Any = self.devices.get(device.0)?.as_ref();
+        device.downcast_ref()
+    }
+
+    pub fn device_mut<D: Device>(&mut self, device: DeviceId) -> Option<&mut D> {
+        let device: &mut dyn Any = self.devices.get_mut(device.0)?.as_mut();
+        device.downcast_mut()
+    }
+
+    // the device mapped at the address, and the offset into it
+    fn resolve(&self, address: &Address) -> Option<(usize, u16)> {
+        let address = address.0 as u32;
+
+        self.mappings
+            .iter()
+            .rev()
+            .find(|mapping| mapping.range.contains(&address))
+            .map(|mapping| (mapping.device, ((address - mapping.range.start) % mapping.size) as u16))
+    }
+}
+
+impl Memory for Bus {
+    fn peek(&self, address: &Address) -> Value {
+        match self.resolve(address) {
+            Some((device, offset)) => self.devices[device].peek(offset),
+            None => self.open_bus,
+        }
+    }
+
+    fn read(&mut self, address: &Address) -> Value {
+        if let Some((device, offset)) = self.resolve(address) {
+            self.open_bus = self.devices[device].read(offset);
+        }
+
+        self.open_bus
+    }
+
+    fn write(&mut self, address: &Address, value: &Value) {
+        self.open_bus = *value;
+
+        let Some((device, offset)) = self.resolve(address) else {
+            return;
+        };
+
+        let device = &mut self.devices[device];
+        if !device.read_only(offset) {
+            device.write(offset, *value);
+            return;
+        }
+
+        match self.write_policy {
+            WritePolicy::Ignore => {}
+            WritePolicy::Log => self.rom_writes.push((*address, *value)),
+            WritePolicy::Error => self.write_fault = self.write_fault.or(Some((*address, *value))),
+        }
+    }
+
+    fn tick(&mut self) {
+        for device in &mut self.devices {
+            device.tick();
+        }
+    }
+
+    fn take_write_fault(&mut self) -> Option<(Address, Value)> {
+        self.write_fault.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::bus::{Bus, Device, DeviceId, WritePolicy};
+    use crate::memory::Memory;
+    use crate::memory::ram::Ram;
+    use crate::memory::rom::Rom;
+    use crate::processor::cmos::{Cmos, CmosProcessor};
+    use crate::processor::disassembler::disassemble;
+    use crate::processor::{ExecutionError, Value};
+
+    // counts the cycles it has seen, readable as a byte
+    #[derive(Default)]
+    struct Timer {
+        cycles: u64,
+    }
+
+    impl Device for Timer {
+        fn peek(&self, _offset: u16) -> Value {
+            self.cycles as Value
+        }
+
+        fn len(&self) -> usize {
+            1
+        }
+
+        fn write(&mut self, _offset: u16, _value: Value) {
+            self.cycles = 0;
+        }
+
+        fn tick(&mut self) {
+            self.cycles += 1;
+        }
+    }
+
+    #[test]
+    fn test_map() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x0800));
+        let upper = bus.add(Ram::new(0x8000));
+        bus.map(0x0000..0x0800, ram);
+        bus.map(0x8000..0x10000, upper);
+
+        bus.write(&Address(0x0123), &0x42);
+        assert_eq!(bus.read(&Address(0x0123)), 0x42);
+        assert_eq!(bus.device::<Ram>(ram).unwrap().peek(0x0123), 0x42);
+
+        // the top of memory is offset $7FFF into the second device
+        bus.write(&Address(0xffff), &0x24);
+        assert_eq!(bus.device::<Ram>(upper).unwrap().peek(0x7fff), 0x24);
+
+        // the wrong type of device isn't handed out
+        assert!(bus.device::<Timer>(ram).is_none());
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x0800));
+        bus.map_mirrored(0x0000..0x2000, 0x0800, ram);
+
+        bus.write(&Address(0x1805), &0x42);
+        assert_eq!(bus.read(&Address(0x0005)), 0x42);
+        assert_eq!(bus.read(&Address(0x0805)), 0x42);
+        assert_eq!(bus.read(&Address(0x1005)), 0x42);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small to map")]
+    fn test_map_past_device() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x0800));
+        bus.map(0x0000..0x2000, ram);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small to map")]
+    fn test_mirror_past_device() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x4000));
+        bus.map_mirrored(0x8000..0x10000, 0x8000, ram);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x10000));
+        let io = bus.add(Ram::new(0x10));
+        bus.map(0x0000..0x10000, ram);
+        bus.map(0xd000..0xd010, io);
+
+        // the range mapped last wins where they overlap
+        bus.write(&Address(0xd004), &0x42);
+        assert_eq!(bus.device::<Ram>(io).unwrap().peek(0x0004), 0x42);
+        assert_eq!(bus.device::<Ram>(ram).unwrap().peek(0xd004), 0x00);
+
+        bus.write(&Address(0xd010), &0x24);
+        assert_eq!(bus.device::<Ram>(ram).unwrap().peek(0xd010), 0x24);
+    }
+
+    #[test]
+    fn test_open_bus() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x0100));
+        bus.map(0x0000..0x0100, ram);
+
+        bus.write(&Address(0x0010), &0x42);
+        bus.read(&Address(0x0010));
+        assert_eq!(bus.read(&Address(0x4000)), 0x42);
+
+        // writing to nothing goes nowhere
+        bus.write(&Address(0x4000), &0x24);
+        assert_eq!(bus.read(&Address(0x0010)), 0x42);
+    }
+
+    // a flag that's cleared by reading it, like an interrupt flag register
+    struct Flag(bool);
+
+    impl Device for Flag {
+        fn peek(&self, _offset: u16) -> Value {
+            self.0 as Value
+        }
+
+        fn read(&mut self, offset: u16) -> Value {
+            let value = self.peek(offset);
+            self.0 = false;
+            value
+        }
+
+        fn len(&self) -> usize {
+            1
+        }
+
+        fn write(&mut self, _offset: u16, _value: Value) {
+            self.0 = true;
+        }
+    }
+
+    #[test]
+    fn test_read_side_effects() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x10000));
+        let flag = bus.add(Flag(true));
+        bus.map(0x0000..0x10000, ram);
+        bus.map(0xd000..0xd001, flag);
+
+        for (i, byte) in [0xAD, 0x00, 0xD0].iter().enumerate() { // LDA $D000
+            bus.write(&Address(0x0200 + i as u16), byte);
+        }
+        bus.write(&Address(0xd000), &0x00);
+
+        // looking doesn't clear it
+        assert_eq!(disassemble::<Cmos, _>(&bus, Address(0x0200)).unwrap().to_string(), "LDA $D000");
+        assert_eq!(bus.peek(&Address(0xd000)), 0x01);
+        assert_eq!(bus.peek(&Address(0xd000)), 0x01);
+
+        let mut processor = CmosProcessor::with_memory(bus);
+        processor.set_program_counter(0x0200);
+        processor.step().unwrap();
+
+        assert_eq!(processor.accumulator(), 0x01);
+        assert_eq!(processor.bus().peek(&Address(0xd000)), 0x00);
+    }
+
+    // ram below $8000 and a rom with STA $8000, INX above
+    fn rom_bus(write_policy: WritePolicy) -> (Bus, DeviceId) {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x8000));
+        let mut rom = vec![0x00; 0x8000];
+        rom[0x0100..0x0104].copy_from_slice(&[0x8D, 0x00, 0x80, 0xE8]);
+        let rom = bus.add(Rom::new(&rom));
+        bus.map(0x0000..0x8000, ram);
+        bus.map(0x8000..0x10000, rom);
+        bus.set_write_policy(write_policy);
+        (bus, rom)
+    }
+
+    #[test]
+    fn test_rom_write_ignored() {
+        let (mut bus, rom) = rom_bus(WritePolicy::Ignore);
+
+        bus.write(&Address(0x8000), &0x42);
+        assert_eq!(bus.read(&Address(0x8000)), 0x00);
+        assert_eq!(bus.device::<Rom>(rom).unwrap().peek(0x0000), 0x00);
+        assert!(bus.rom_writes().is_empty());
+        assert_eq!(bus.take_write_fault(), None);
+    }
+
+    #[test]
+    fn test_rom_write_logged() {
+        let (mut bus, _) = rom_bus(WritePolicy::Log);
+
+        bus.write(&Address(0x8000), &0x42);
+        bus.write(&Address(0x0000), &0x24);
+        bus.write(&Address(0xffff), &0x43);
+        assert_eq!(bus.read(&Address(0x8000)), 0x00);
+        assert_eq!(bus.rom_writes(), vec![(Address(0x8000), 0x42), (Address(0xffff), 0x43)]);
+        assert!(bus.rom_writes().is_empty());
+    }
+
+    #[test]
+    fn test_rom_write_error() {
+        let (bus, _) = rom_bus(WritePolicy::Error);
+        let mut processor = CmosProcessor::with_memory(bus);
+        processor.set_program_counter(0x8100);
+        processor.set_accumulator(0x42);
+
+        // the store still finishes, then fails the step
+        assert_eq!(processor.step(), Err(ExecutionError::WriteToRom { address: 0x8000, value: 0x42 }));
+        assert_eq!(processor.program_counter(), 0x8103);
+        assert_eq!(processor.cycles(), 4);
+        assert_eq!(processor.step(), Ok(2));
+
+        // tick() fails on the cycle of the write
+        processor.set_program_counter(0x8100);
+        assert_eq!(processor.tick(), Ok(false));
+        assert_eq!(processor.tick(), Ok(false));
+        assert_eq!(processor.tick(), Ok(false));
+        assert_eq!(processor.tick(), Err(ExecutionError::WriteToRom { address: 0x8000, value: 0x42 }));
+        assert_eq!(processor.program_counter(), 0x8103);
+    }
+
+    #[test]
+    fn test_device_tick() {
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x10000));
+        let timer = bus.add(Timer::default());
+        bus.map(0x0000..0x10000, ram);
+        bus.map(0xd000..0xd001, timer);
+
+        for (i, byte) in [0xEA, 0xAD, 0x00, 0xD0].iter().enumerate() { // NOP, LDA $D000
+            bus.write(&Address(0x0200 + i as u16), byte);
+        }
+
+        let mut processor = CmosProcessor::with_memory(bus);
+        processor.set_program_counter(0x0200);
+        processor.step().unwrap();
+        processor.step().unwrap();
+
+        // ticked once a cycle, the read of the timer happening on the sixth
+        assert_eq!(processor.accumulator(), 5);
+        assert_eq!(processor.bus().device::<Timer>(timer).unwrap().cycles, 6);
+    }
+}
@@ -0,0 +1,180 @@
+use std::ops::Range;
+use crate::memory::banked::Banked;
+
+// the NES UxROM board, mapper 2. the first 16K bank of the PRG ROM is switched by writing its
+// number anywhere in the ROM, the last is fixed above it. map it at $8000..$10000
+pub fn uxrom(prg_rom: &[u8]) -> Banked {
+    assert!(!prg_rom.is_empty() && prg_rom.len().is_multiple_of(0x4000), "UxROM takes 16K banks of PRG ROM");
+
+    let mut banked = Banked::new();
+    let rom = banked.add_rom(prg_rom);
+    let switched = banked.add_window(0x0000..0x4000, rom, 0);
+    banked.add_window(0x4000..0x8000, rom, prg_rom.len() / 0x4000 - 1);
+
+    banked.add_register(0x0000..0x8000, move |banked, _, value| {
+        banked.select(switched, rom, value as usize);
+    });
+
+    banked
+}
+
+// ram larger than the address space, like the 128K machines. the window shows the bank of ram,
+// counted in the window's size, last written to the register. everything else is fixed to the
+// bottom 64K. it starts out as a plain 64K of ram, map it at $0000..$10000. writes to the
+// register's address select a bank instead of reaching the ram, so that byte can't be written
+pub fn paged_ram(size: usize, window: Range<u32>, register: u16) -> Banked {
+    assert!(size > 0x10000, "paged ram needs more than the address space");
+    assert!(window.start < window.end && window.end <= 0x10000, "{:#x?} isn't a range of addresses", window);
+
+    let window_size = window.end - window.start;
+    assert!(
+        0x10000u32.is_multiple_of(window_size) && window.start.is_multiple_of(window_size),
+        "the window has to split the 64K evenly and sit on a boundary of its size",
+    );
+
+    let bank = (window.start / window_size) as usize;
+
+    let mut banked = Banked::new();
+    let ram = banked.add_ram(size);
+    banked.add_window(0x0000..0x10000, ram, 0);
+    let paged = banked.add_window(window, ram, bank);
+
+    banked.add_register(register as u32..register as u32 + 1, move |banked, _, value| {
+        banked.select(paged, ram, value as usize);
+    });
+
+    banked
+}
+
+// rom over ram, like the C64's BASIC and KERNAL. reads see the rom while bit 0 of the register
+// is set, which it is to start with, and the ram under it when it's clear. writes always go to
+// the ram. map it at $0000..$10000
+pub fn shadowed_rom(rom: &[u8], address: u16, register: u16) -> Banked {
+    assert!(!rom.is_empty() && (address as usize).is_multiple_of(rom.len()), "the rom has to sit on a boundary of its size");
+    assert!(address as usize + rom.len() <= 0x10000, "the rom runs past the top of memory");
+
+    let start = address as u32;
+    let end = start + rom.len() as u32;
+    let ram_bank = address as usize / rom.len();
+
+    let mut banked = Banked::new();
+    let ram = banked.add_ram(0x10000);
+    let rom = banked.add_rom(rom);
+    banked.add_window(0x0000..0x10000, ram, 0);
+    let shadowed = banked.add_window(start..end, rom, 0);
+    banked.select_write(shadowed, ram, ram_bank);
+
+    banked.add_register(register as u32..register as u32 + 1, move |banked, _, value| {
+        if value & 1 == 1 {
+            banked.select_read(shadowed, rom, 0);
+        } else {
+            banked.select_read(shadowed, ram, ram_bank);
+        }
+    });
+
+    banked
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::bus::{Bus, Device};
+    use crate::memory::mappers::{paged_ram, shadowed_rom, uxrom};
+    use crate::memory::ram::Ram;
+    use crate::memory::Memory;
+    use crate::processor::cmos::CmosProcessor;
+
+    #[test]
+    fn test_uxrom() {
+        // four banks, each filled with its number
+        let prg_rom: Vec<u8> = (0..4u8).flat_map(|bank| [bank; 0x4000]).collect();
+
+        let mut bus = Bus::new();
+        let ram = bus.add(Ram::new(0x0800));
+        let mapper = bus.add(uxrom(&prg_rom));
+        bus.map_mirrored(0x0000..0x2000, 0x0800, ram);
+        bus.map(0x8000..0x10000, mapper);
+
+        assert_eq!(bus.peek(&Address(0x8000)), 0);
+        assert_eq!(bus.peek(&Address(0xc000)), 3);
+
+        bus.write(&Address(0x8123), &2);
+        assert_eq!(bus.peek(&Address(0xbfff)), 2);
+        assert_eq!(bus.peek(&Address(0xffff)), 3);
+    }
+
+    #[test]
+    fn test_uxrom_switched_by_program() {
+        // the fixed bank has LDA #$01, STA $8000, LDX $8000 at $C000
+        let mut prg_rom = vec![0xEA; 0x8000];
+        prg_rom[0x4000] = 0xA9;
+        prg_rom[0x4001] = 0x01;
+        prg_rom[0x4002] = 0x8D;
+        prg_rom[0x4003] = 0x00;
+        prg_rom[0x4004] = 0x80;
+        prg_rom[0x4005] = 0xAE;
+        prg_rom[0x4006] = 0x00;
+        prg_rom[0x4007] = 0x80;
+
+        let mut bus = Bus::new();
+        let mapper = bus.add(uxrom(&prg_rom));
+        bus.map(0x8000..0x10000, mapper);
+
+        let mut processor = CmosProcessor::with_memory(bus);
+        processor.set_program_counter(0xc000);
+        for _ in 0..3 {
+            processor.step().unwrap();
+        }
+
+        // $8000 held a NOP from bank 0 until bank 1, the fixed bank, was switched in below it
+        assert_eq!(processor.x(), 0xA9);
+        assert_eq!(processor.program_counter(), 0xc008);
+    }
+
+    #[test]
+    fn test_paged_ram() {
+        let mut memory = paged_ram(0x20000, 0xc000..0x10000, 0x00ff);
+
+        // a plain 64K to start with
+        memory.write(0xc000, 0x42);
+        memory.write(0x0123, 0x24);
+
+        memory.write(0x00ff, 7);
+        assert_eq!(memory.peek(0xc000), 0x00);
+        memory.write(0xc000, 0x43);
+        assert_eq!(memory.peek(0x0123), 0x24);
+
+        memory.write(0x00ff, 3);
+        assert_eq!(memory.peek(0xc000), 0x42);
+        memory.write(0x00ff, 7);
+        assert_eq!(memory.peek(0xc000), 0x43);
+    }
+
+    #[test]
+    #[should_panic(expected = "sit on a boundary of its size")]
+    fn test_paged_ram_unaligned() {
+        paged_ram(0x20000, 0x1000..0x5000, 0x00ff);
+    }
+
+    #[test]
+    #[should_panic(expected = "runs past the top of memory")]
+    fn test_shadowed_rom_past_top() {
+        shadowed_rom(&[0x42; 0x3000], 0xf000, 0x0001);
+    }
+
+    #[test]
+    fn test_shadowed_rom() {
+        let mut memory = shadowed_rom(&[0x42; 0x2000], 0xa000, 0x0001);
+
+        memory.write(0xa000, 0x24);
+        assert_eq!(memory.peek(0xa000), 0x42);
+
+        memory.write(0x0001, 0x00);
+        assert_eq!(memory.peek(0xa000), 0x24);
+        assert_eq!(memory.peek(0xbfff), 0x00);
+
+        memory.write(0x0001, 0x01);
+        assert_eq!(memory.peek(0xa000), 0x42);
+        assert_eq!(memory.peek(0x9fff), 0x00);
+    }
+}
@@ -3,8 +3,53 @@ use crate::processor::Value;
 
 pub mod vec_memory;
 pub mod address;
+pub mod banked;
+pub mod bus;
+pub mod mappers;
+pub mod ram;
+pub mod rom;
 
 pub trait Memory {
-    fn read(&self, address: &Address) -> Value;
+    // what's at the address, without any of the side effects reading it on the bus has.
+    // for debuggers and disassemblers looking at memory the processor isn't accessing
+    fn peek(&self, address: &Address) -> Value;
+
+    // a read by the processor on the bus, which some devices react to, like an i/o
+    // register clearing a flag once it's been read
+    fn read(&mut self, address: &Address) -> Value {
+        self.peek(address)
+    }
+
     fn write(&mut self, address: &Address, value: &Value);
+
+    // called once for every cycle the processor runs, after its access to the bus
+    fn tick(&mut self) {}
+
+    // a write since the last call the memory refused, and failed the step it happened in
+    fn take_write_fault(&mut self) -> Option<(Address, Value)> {
+        None
+    }
+}
+
+// lets a processor work on memory it only borrows
+impl<M: Memory + ?Sized> Memory for &mut M {
+    fn peek(&self, address: &Address) -> Value {
+        (**self).peek(address)
+    }
+
+    fn read(&mut self, address: &Address) -> Value {
+        (**self).read(address)
+    }
+
+    fn write(&mut self, address: &Address, value: &Value) {
+        (**self).write(address, value)
+    }
+
+    fn tick(&mut self) {
+        (**self).tick()
+    }
+
+    fn take_write_fault(&mut self) -> Option<(Address, Value)> {
+        (**self).take_write_fault()
+    }
 }
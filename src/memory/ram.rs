@@ -0,0 +1,25 @@
+use crate::memory::bus::Device;
+use crate::processor::Value;
+
+// plain read/write memory of any size, for mapping onto a bus
+pub struct Ram(Vec<u8>);
+
+impl Ram {
+    pub fn new(size: usize) -> Self {
+        Self(vec![0; size])
+    }
+}
+
+impl Device for Ram {
+    fn peek(&self, offset: u16) -> Value {
+        self.0[offset as usize]
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn write(&mut self, offset: u16, value: Value) {
+        self.0[offset as usize] = value;
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::memory::bus::Device;
+use crate::processor::Value;
+
+// read only memory, what the bus does with a write to it is up to its write policy
+pub struct Rom(Vec<u8>);
+
+impl Rom {
+    pub fn new(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self(fs::read(path)?))
+    }
+}
+
+impl Device for Rom {
+    fn peek(&self, offset: u16) -> Value {
+        self.0[offset as usize]
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn write(&mut self, _offset: u16, _value: Value) {}
+
+    fn read_only(&self, _offset: u16) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use crate::memory::bus::Device;
+    use crate::memory::rom::Rom;
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("emulator_6502_rom_{}.bin", std::process::id()));
+        fs::write(&path, [0x01, 0x02, 0x03]).unwrap();
+
+        let rom = Rom::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(rom.len(), 3);
+        assert_eq!(rom.peek(0x0002), 0x03);
+        assert!(Rom::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_write_ignored() {
+        let mut rom = Rom::new(&[0x42]);
+        rom.write(0x0000, 0x24);
+        assert_eq!(rom.peek(0x0000), 0x42);
+    }
+}
@@ -12,7 +12,7 @@ impl Default for VecMemory {
 
 
 impl Memory for VecMemory {
-    fn read(&self, address: &Address) -> Value {
+    fn peek(&self, address: &Address) -> Value {
         self.0[address.0 as usize]
     }
     fn write(&mut self, address: &Address, value: &Value) {
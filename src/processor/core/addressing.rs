@@ -0,0 +1,571 @@
+use std::ops::Add;
+use crate::memory::address::{Address, AddressMode, ZeroPageAddress};
+use crate::memory::Memory;
+use crate::processor::core::Processor;
+use crate::processor::op_codes::OpCode;
+use crate::processor::variant::Variant;
+use crate::processor::{ExecutionError, Value};
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    // reads the operand bytes following the op code at the program counter. an instruction
+    // without operand bytes still reads the byte after the op code, only the 65C02's
+    // single cycle NOPs don't
+    pub(crate) fn read_operand(&mut self, op_code: &OpCode) -> AddressMode {
+        let operand_address = Address(self.program_counter).add(1u8);
+        let execution_metrics = &op_code.execution_metrics;
+
+        let mut operand = [0; 2];
+        for (i, byte) in operand.iter_mut().enumerate().take(execution_metrics.bytes as usize - 1) {
+            *byte = self.read_bus(operand_address.add(i as u8));
+        }
+
+        if execution_metrics.bytes == 1 && execution_metrics.cycles > 1 {
+            self.read_bus(operand_address);
+        }
+
+        AddressMode::from_operand(&op_code.address_mode, operand[0], operand[1])
+    }
+
+    pub(crate) fn read_address(&mut self, address_mode: &AddressMode) -> Result<(Value, u8), ExecutionError> {
+        match address_mode {
+            AddressMode::Implied => Ok((0, 0)),
+            AddressMode::Accumulator => Ok((self.accumulator, 0)),
+            AddressMode::Immediate(value) => Ok((*value, 0)),
+            _ => {
+                let (address, additional_cycles) = self.translate_address(address_mode)?;
+                self.fix_up_indexing(address_mode, address, false);
+                Ok((self.read_bus(address), additional_cycles))
+            }
+        }
+    }
+
+    // works out the address the address mode refers to, making the reads of any pointer
+    // on the way. indexed modes leave the cycle fixing up the high byte to fix_up_indexing
+    pub(crate) fn translate_address(&mut self, address_mode: &AddressMode) -> Result<(Address, u8), ExecutionError> {
+        let translated = match address_mode {
+            AddressMode::Implied | AddressMode::Accumulator | AddressMode::Immediate(_) => {
+                return Err(ExecutionError::AddressModeMismatch(address_mode.kind()));
+            }
+            AddressMode::ZeroPage(zp_address) => (self.address_zeropage(zp_address), 0),
+            AddressMode::ZeroPageX(zp_address) => (self.address_zeropage_x(zp_address), 0),
+            AddressMode::ZeroPageY(zp_address) => (self.address_zeropage_y(zp_address), 0),
+            AddressMode::Absolute(address) => (self.address_absolute(address), 0),
+            AddressMode::AbsoluteX(address) => self.address_absolute_x(address),
+            AddressMode::AbsoluteY(address) => self.address_absolute_y(address),
+            AddressMode::Indirect(address) => (self.address_indirect(address), 0),
+            AddressMode::PreIndexedIndirectX(zp_address) => (self.address_preindexed_indirect_x(zp_address), 0),
+            AddressMode::PostIndexedIndirectY(zp_address) => self.address_postindexed_indirect_y(zp_address),
+            // only translated for a branch that is taken, so the penalty cycles always apply
+            AddressMode::Relative(offset) => self.address_relative(*offset),
+            AddressMode::ZeroPageIndirect(zp_address) => (self.address_zeropage_indirect(zp_address), 0),
+            AddressMode::AbsoluteIndexedIndirectX(address) => (self.address_absolute_indexed_indirect_x(address), 0),
+            // the zero page byte that gets tested, the branch target is found with address_relative
+            AddressMode::ZeroPageRelative(zp_address, _) => (self.address_zeropage(zp_address), 0),
+        };
+
+        Ok(translated)
+    }
+
+    // indexing adds to the low byte of the address first and reads from there while the
+    // carry goes into the high byte. a read staying within the page takes that read as it
+    // is, anything else reads again once the address is right, which writes have to
+    // do anyway as they can't take back a write to the wrong address
+    pub(crate) fn fix_up_indexing(&mut self, address_mode: &AddressMode, address: Address, write: bool) {
+        if !matches!(address_mode, AddressMode::AbsoluteX(_) | AddressMode::AbsoluteY(_) | AddressMode::PostIndexedIndirectY(_)) {
+            return;
+        }
+
+        // the index is at most $FF, so crossing a page always carries into the high byte
+        let page_crossed = self.page_crossed(address_mode, address);
+        if page_crossed {
+            self.fix_up_read(Address(address.0.wrapping_sub(0x100)));
+        } else if write {
+            self.read_bus(address);
+        }
+    }
+
+    fn page_crossed(&self, address_mode: &AddressMode, address: Address) -> bool {
+        let index = match address_mode {
+            AddressMode::AbsoluteX(_) => self.x,
+            _ => self.y,
+        };
+
+        (address.0 & 0x00ff) < index as u16
+    }
+
+    // the cycle spent carrying into the high byte of an address. the 65C02 reads the
+    // last byte of the instruction again rather than the invalid address
+    pub(crate) fn fix_up_read(&mut self, invalid_address: Address) {
+        if V::PAGE_CROSS_READS_INVALID_ADDRESS {
+            self.read_bus(invalid_address);
+        } else {
+            self.read_bus(Address(self.program_counter.wrapping_sub(1)));
+        }
+    }
+
+    fn address_zeropage(&self, zp_address: &ZeroPageAddress) -> Address {
+        zp_address.upgrade()
+    }
+
+    // the base address is read while the index is added
+    fn address_zeropage_x(&mut self, zp_address: &ZeroPageAddress) -> Address {
+        self.read_bus(zp_address.upgrade());
+        let zp_address = zp_address.wrapping_add(self.x);
+        zp_address.upgrade()
+    }
+
+    fn address_zeropage_y(&mut self, zp_address: &ZeroPageAddress) -> Address {
+        self.read_bus(zp_address.upgrade());
+        let zp_address = zp_address.wrapping_add(self.y);
+        zp_address.upgrade()
+    }
+
+    fn address_absolute(&self, address: &Address) -> Address {
+        *address
+    }
+
+    fn address_indirect(&mut self, address: &Address) -> Address {
+        let address_low = self.read_bus(*address);
+
+        // the NMOS part doesn't carry into the high byte of the pointer,
+        // so a pointer at $xxFF takes its high byte from $xx00. the 65C02
+        // spends a cycle on the carry
+        let high_address = if V::INDIRECT_PAGE_WRAP {
+            Address((address.0 & 0xff00) | (address.0.wrapping_add(1) & 0x00ff))
+        } else {
+            self.fix_up_read(*address);
+            address.add(1u8)
+        };
+
+        let address_high = self.read_bus(high_address);
+        Address::from_bytes(address_low, address_high)
+    }
+
+    // the displacement is taken from the next instruction, which the program counter
+    // already points at. taking the branch costs a cycle, crossing a page another
+    fn address_relative(&self, offset: i8) -> (Address, u8) {
+        let next_instruction = Address(self.program_counter);
+        let target = Address(next_instruction.0.wrapping_add(offset as u16));
+        let page_crossed = (next_instruction.0 & 0xff00) != (target.0 & 0xff00);
+
+        (target, 1 + page_crossed as u8)
+    }
+
+    fn address_absolute_x(&self, address: &Address) -> (Address, u8) {
+        let (address, page_crossed) = address.add_check_page_cross(self.x);
+        (address, page_crossed as u8)
+    }
+
+    fn address_absolute_y(&self, address: &Address) -> (Address, u8) {
+        let (address, page_crossed) = address.add_check_page_cross(self.y);
+        (address, page_crossed as u8)
+    }
+
+    // a pointer at $FF takes its high byte from $00, on both variants the pointer
+    // never leaves the zero page
+    fn read_zeropage_pointer(&mut self, zp_address: &ZeroPageAddress) -> Address {
+        let address_low = self.read_bus(zp_address.upgrade());
+        let address_high = self.read_bus(zp_address.wrapping_add(1).upgrade());
+        Address::from_bytes(address_low, address_high)
+    }
+
+    fn address_preindexed_indirect_x(&mut self, zp_address: &ZeroPageAddress) -> Address {
+        // preindexed, add x to lookup address while reading the base
+        self.read_bus(zp_address.upgrade());
+        let lookup_address = zp_address.wrapping_add(self.x);
+        self.read_zeropage_pointer(&lookup_address)
+    }
+
+    fn address_zeropage_indirect(&mut self, zp_address: &ZeroPageAddress) -> Address {
+        self.read_zeropage_pointer(zp_address)
+    }
+
+    fn address_absolute_indexed_indirect_x(&mut self, address: &Address) -> Address {
+        // preindexed, add x to the pointer
+        self.read_bus(Address(self.program_counter.wrapping_sub(1)));
+        let lookup_address = address.add(self.x);
+
+        let address_low = self.read_bus(lookup_address);
+        let address_high = self.read_bus(lookup_address.add(1u8));
+        Address::from_bytes(address_low, address_high)
+    }
+
+    fn address_postindexed_indirect_y(&mut self, zp_address: &ZeroPageAddress) -> (Address, u8) {
+        let address = self.read_zeropage_pointer(zp_address);
+
+        // post indexed, add y to lookup address
+        let (address, page_crossed) = address.add_check_page_cross(self.y);
+
+        (address, page_crossed as u8)
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::{Address, AddressMode, ZeroPageAddress};
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::{Cmos, CmosProcessor};
+    use crate::processor::core::Processor;
+    use crate::processor::nmos::Nmos;
+    use crate::processor::variant::Variant;
+    use crate::processor::{Instruction};
+
+    #[test]
+    fn test_address_immediate() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(5)).unwrap();
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_zeropage() {
+        let zp_address = ZeroPageAddress(32);
+        let address = zp_address.upgrade();
+        let mut memory = VecMemory::default();
+        memory.write(&address, &5);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::ADC, &AddressMode::ZeroPage(zp_address)).unwrap();
+
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_zeropage_x() {
+        let zp_address = ZeroPageAddress(32);
+        let address = zp_address.upgrade();
+
+        let mut memory = VecMemory::default();
+        memory.write(&address, &5);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // 30 + 2 = 32 so we should read 5 at address 32
+        processor.x = 2;
+        let zp_address = ZeroPageAddress(30);
+
+        processor.execute(&Instruction::ADC, &AddressMode::ZeroPageX(zp_address)).unwrap();
+
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_zeropage_y() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0020), &5);
+        memory.write(&Address(0x0001), &7);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // 30 + 2 = 32 so we should read 5 at address 32
+        processor.y = 2;
+        processor.execute(&Instruction::LDX, &AddressMode::ZeroPageY(ZeroPageAddress(30))).unwrap();
+        assert_eq!(processor.x, 5);
+
+        // indexing wraps around within the zero page
+        processor.y = 0x02;
+        processor.execute(&Instruction::LDX, &AddressMode::ZeroPageY(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.x, 7);
+
+        processor.execute(&Instruction::STX, &AddressMode::ZeroPageY(ZeroPageAddress(0xfe))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0000)), 7);
+        assert_eq!(processor.memory.peek(&Address(0x0100)), 0);
+    }
+
+    #[test]
+    fn test_address_relative() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x1000;
+
+        // not taken, no penalty
+        let cycles = processor.execute(&Instruction::BCS, &AddressMode::Relative(0x10)).unwrap();
+        assert_eq!(cycles, 2);
+        assert_eq!(processor.program_counter, 0x1002);
+
+        // taken, relative to the next instruction
+        let cycles = processor.execute(&Instruction::BCC, &AddressMode::Relative(0x10)).unwrap();
+        assert_eq!(cycles, 3);
+        assert_eq!(processor.program_counter, 0x1014);
+
+        // taken backwards across a page
+        let cycles = processor.execute(&Instruction::BCC, &AddressMode::Relative(-0x20)).unwrap();
+        assert_eq!(cycles, 4);
+        assert_eq!(processor.program_counter, 0x0ff6);
+
+        // taken forwards across a page
+        processor.program_counter = 0x10f0;
+        let cycles = processor.execute(&Instruction::BRA, &AddressMode::Relative(0x7f)).unwrap();
+        assert_eq!(cycles, 4);
+        assert_eq!(processor.program_counter, 0x1171);
+    }
+
+    #[test]
+    fn test_address_absolute() {
+        let address = Address(0xcabd);
+
+        let mut memory = VecMemory::default();
+        memory.write(&address, &5);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.execute(&Instruction::ADC, &AddressMode::Absolute(address)).unwrap();
+
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_absolute_x() {
+        let address = Address(0xcabd);
+
+        let mut memory = VecMemory::default();
+        memory.write(&address, &5);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // cabb + 2 = cabd so we should read 5 at address 32
+        processor.x = 2;
+        let address = Address(0xcabb);
+
+        processor.execute(&Instruction::ADC, &AddressMode::AbsoluteX(address)).unwrap();
+
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_absolute_y() {
+        let address = Address(0xcabd);
+
+        let mut memory = VecMemory::default();
+        memory.write(&address, &5);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // cabb + 2 = cabd so we should read 5 at address 32
+        processor.y = 2;
+        let address = Address(0xcabb);
+
+        processor.execute(&Instruction::ADC, &AddressMode::AbsoluteY(address)).unwrap();
+
+        assert_eq!(processor.accumulator, 5);
+    }
+
+    #[test]
+    fn test_address_preindexed_indirect_x() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0010), &0xbb);
+        memory.write(&Address(0x0011), &0xca);
+        memory.write(&Address(0xcabb), &0x32);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x08;
+
+        // 0x00 = 8, x = 8, 8+8=0x10, 0x10,11 -> 0xbb,0xca -- 0xcabb -> 0x32
+        processor.execute(
+            &Instruction::ADC,
+            &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0x08)),
+        ).unwrap();
+
+        assert_eq!(processor.accumulator, 0x32);
+    }
+
+    #[test]
+    fn test_address_postindexed_indirect_y() {
+        let mut memory = VecMemory::default();
+
+        memory.write(&Address(0x0010), &(0xbb - 0x20));
+        memory.write(&Address(0x0011), &0xca);
+        memory.write(&Address(0xcabb), &0x32);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.y = 0x20;
+
+        // 0x00 = 8, x = 8, 8+8=0x10, 0x10,11 -> 0xbb,0xca -- 0xcabb -> 0x32
+        processor.execute(
+            &Instruction::ADC,
+            &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0x10)),
+        ).unwrap();
+
+        assert_eq!(processor.accumulator, 0x32);
+    }
+
+    #[test]
+    fn test_address_preindexed_indirect_x_pointer_wrap() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x00ff), &0xbb);
+        memory.write(&Address(0x0000), &0xca);
+        memory.write(&Address(0x0100), &0x12);
+        memory.write(&Address(0xcabb), &0x32);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x0f;
+
+        // $f0 + $0f = $ff, the high byte comes from $00 rather than $0100
+        processor.execute(
+            &Instruction::ADC,
+            &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0xf0)),
+        ).unwrap();
+
+        assert_eq!(processor.accumulator, 0x32);
+    }
+
+    #[test]
+    fn test_address_postindexed_indirect_y_pointer_wrap() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x00ff), &(0xbb - 0x20));
+        memory.write(&Address(0x0000), &0xca);
+        memory.write(&Address(0x0100), &0x12);
+        memory.write(&Address(0xcabb), &0x32);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.y = 0x20;
+
+        // the high byte comes from $00 rather than $0100
+        processor.execute(
+            &Instruction::ADC,
+            &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0xff)),
+        ).unwrap();
+
+        assert_eq!(processor.accumulator, 0x32);
+    }
+
+    #[test]
+    fn test_address_zeropage_indirect() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0010), &0xbb);
+        memory.write(&Address(0x0011), &0xca);
+        memory.write(&Address(0xcabb), &0x32);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x01;
+        processor.y = 0x01;
+
+        // not indexed by either register
+        processor.execute(&Instruction::ADC, &AddressMode::ZeroPageIndirect(ZeroPageAddress(0x10))).unwrap();
+
+        assert_eq!(processor.accumulator, 0x32);
+    }
+
+    #[test]
+    fn test_address_absolute_indexed_indirect_x() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x1012), &0x34);
+        memory.write(&Address(0x1013), &0x12);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x02;
+
+        // $1010 + 2 = $1012 -> $1234
+        let cycles = processor.execute(&Instruction::JMP, &AddressMode::AbsoluteIndexedIndirectX(Address(0x1010))).unwrap();
+
+        assert_eq!(cycles, 6);
+        assert_eq!(processor.program_counter, 0x1234);
+    }
+
+    // memory with a marker byte at $0100 and $1300, where a pointer that fails to wrap
+    // would take its high byte from
+    fn boundary_memory() -> VecMemory {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0100), &0x66);
+        memory.write(&Address(0x1300), &0x66);
+        memory
+    }
+
+    fn assert_zeropage_pointer_wrap<V: Variant>() {
+        // (zp,X) with the pointer at $ff
+        let mut memory = boundary_memory();
+        memory.write(&Address(0x00ff), &0x34);
+        memory.write(&Address(0x0000), &0x12);
+        memory.write(&Address(0x1234), &0x01);
+        let mut processor = Processor::<_, V>::with_memory(&mut memory);
+        processor.execute(&Instruction::LDA, &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x01);
+
+        // (zp,X) with the index carrying the pointer onto $ff
+        processor.x = 0x7f;
+        processor.execute(&Instruction::LDA, &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0x80))).unwrap();
+        assert_eq!(processor.accumulator, 0x01);
+
+        // (zp,X) with the index wrapping the pointer past $ff to $00
+        processor.x = 0x01;
+        processor.memory.write(&Address(0x0001), &0x12);
+        processor.execute(&Instruction::LDA, &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x00);
+        processor.memory.write(&Address(0x1212), &0x02);
+        processor.execute(&Instruction::LDA, &AddressMode::PreIndexedIndirectX(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x02);
+
+        // (zp),Y with the pointer at $ff
+        processor.y = 0x00;
+        processor.execute(&Instruction::LDA, &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x01);
+
+        // (zp),Y with the pointer at $ff and the index crossing a page, which does carry
+        processor.y = 0xcc;
+        processor.memory.write(&Address(0x1300), &0x03);
+        let cycles = processor.execute(&Instruction::LDA, &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x03);
+        assert_eq!(cycles, 6);
+
+        // stores take the same path
+        processor.y = 0x00;
+        processor.accumulator = 0x04;
+        processor.execute(&Instruction::STA, &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x1234)), 0x04);
+        assert_eq!(processor.memory.peek(&Address(0x6634)), 0x00);
+    }
+
+    fn assert_jmp_indirect_boundary<V: Variant>() {
+        let mut memory = boundary_memory();
+        memory.write(&Address(0x12ff), &0x34);
+        memory.write(&Address(0x1200), &0x56);
+
+        let mut processor = Processor::<_, V>::with_memory(&mut memory);
+        processor.execute(&Instruction::JMP, &AddressMode::Indirect(Address(0x12ff))).unwrap();
+
+        // the NMOS part wraps within the page, the 65C02 carries into the next
+        if V::INDIRECT_PAGE_WRAP {
+            assert_eq!(processor.program_counter, 0x5634);
+        } else {
+            assert_eq!(processor.program_counter, 0x6634);
+        }
+    }
+
+    #[test]
+    fn test_zeropage_pointer_wrap() {
+        assert_zeropage_pointer_wrap::<Nmos>();
+        assert_zeropage_pointer_wrap::<Cmos>();
+    }
+
+    #[test]
+    fn test_jmp_indirect_boundary() {
+        assert_jmp_indirect_boundary::<Nmos>();
+        assert_jmp_indirect_boundary::<Cmos>();
+    }
+
+    #[test]
+    fn test_cmos_indirect_boundary() {
+        let mut memory = boundary_memory();
+        memory.write(&Address(0x00ff), &0x34);
+        memory.write(&Address(0x0000), &0x12);
+        memory.write(&Address(0x1234), &0x01);
+        memory.write(&Address(0x12ff), &0x78);
+        memory.write(&Address(0x1300), &0x56);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // (zp) with the pointer at $ff
+        processor.execute(&Instruction::LDA, &AddressMode::ZeroPageIndirect(ZeroPageAddress(0xff))).unwrap();
+        assert_eq!(processor.accumulator, 0x01);
+
+        // (abs,X) is an absolute pointer, it carries into the next page
+        processor.x = 0xff;
+        processor.execute(&Instruction::JMP, &AddressMode::AbsoluteIndexedIndirectX(Address(0x1200))).unwrap();
+        assert_eq!(processor.program_counter, 0x5678);
+    }
+}
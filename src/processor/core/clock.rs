@@ -0,0 +1,478 @@
+use std::cmp::Ordering;
+use crate::memory::address::Address;
+use crate::memory::Memory;
+use crate::processor::core::{Processor, RunState};
+use crate::processor::variant::Variant;
+use crate::processor::{ExecutionError, Registers, Value};
+
+// what an instruction started from, put back while tick() is partway through it
+#[derive(Clone, Copy)]
+pub(crate) struct Snapshot {
+    registers: Registers,
+    cycles: u64,
+    irq: bool,
+    nmi_pending: bool,
+    run_state: RunState,
+}
+
+// an instruction tick() has run some of the cycles of
+#[derive(Clone, Copy)]
+pub(crate) struct InProgress {
+    start: Snapshot,
+    // whether the nmi line went active since the instruction started
+    nmi_latched: bool,
+}
+
+// while an instruction is replayed, the accesses of the cycles already run are answered
+// from what they saw and the accesses after the current cycle are skipped. kept on the
+// processor so the values of one instruction reuse the buffer of the last
+#[derive(Default)]
+pub(crate) struct Replay {
+    // the value read or written on each cycle run so far
+    values: Vec<Value>,
+    // the cycle run for real, none outside tick()
+    cycle: Option<usize>,
+    position: usize,
+}
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    // the chip accesses the bus on every cycle, reading when it has nothing to write,
+    // so every cycle an instruction takes goes through here or write_bus exactly once.
+    // these are reads rather than peeks, the dummy ones included, so i/o registers see
+    // them like on the hardware. the memory is ticked along with each access
+    pub(crate) fn read_bus(&mut self, address: Address) -> Value {
+        let Some(cycle) = self.replay.cycle else {
+            let value = self.memory.read(&address);
+            self.memory.tick();
+            return value;
+        };
+
+        let position = self.replay.position;
+        self.replay.position += 1;
+
+        match position.cmp(&cycle) {
+            Ordering::Less => self.replay.values[position],
+            Ordering::Equal => {
+                let value = self.memory.read(&address);
+                self.memory.tick();
+                self.replay.values.push(value);
+                value
+            }
+            // the cycles after only run to find the instruction isn't over, they
+            // never reach the memory and what they compute is thrown away
+            Ordering::Greater => 0,
+        }
+    }
+
+    pub(crate) fn write_bus(&mut self, address: Address, value: Value) {
+        let Some(cycle) = self.replay.cycle else {
+            self.memory.write(&address, &value);
+            self.memory.tick();
+            return;
+        };
+
+        let position = self.replay.position;
+        self.replay.position += 1;
+
+        if position == cycle {
+            self.memory.write(&address, &value);
+            self.memory.tick();
+            self.replay.values.push(value);
+        }
+    }
+
+    // runs a single cycle, making the one bus access the chip makes on it. returns
+    // whether it was the last cycle of the instruction, the registers only change
+    // once that has run. the instruction is run again up to the next cycle on every
+    // tick, with the reads of the cycles before answered from what they saw, so a
+    // device sees each access on the cycle it happens. as no instruction takes more
+    // than a handful of cycles the replays stay cheap. when the instruction runs on
+    // past the cycle everything it did is put back, the one access made for real
+    // on the cycle is all that remains of it
+    pub fn tick(&mut self) -> Result<bool, ExecutionError> {
+        let irq = self.irq;
+
+        let mut progress = self.in_progress.take().unwrap_or_else(|| {
+            self.replay.values.clear();
+            InProgress { start: self.snapshot(), nmi_latched: false }
+        });
+        progress.nmi_latched |= self.nmi_pending && !progress.start.nmi_pending;
+
+        // the interrupt lines are sampled as they were when the instruction started
+        self.restore(&progress.start);
+        let cycles_run = self.replay.values.len();
+        self.replay.cycle = Some(cycles_run);
+        self.replay.position = 0;
+
+        let result = self.run_instruction();
+
+        self.replay.cycle = None;
+
+        if self.replay.position > cycles_run + 1 {
+            self.restore(&progress.start);
+            self.cycles += self.replay.values.len() as u64;
+            self.in_progress = Some(progress);
+        }
+
+        self.irq = irq;
+        self.nmi_pending |= progress.nmi_latched;
+
+        let result = if self.in_progress.is_some() { Ok(false) } else { result.map(|_| true) };
+        self.check_write_fault(result)
+    }
+
+    // runs the cycles left of an instruction tick() started, returning the cycles
+    // it took in all
+    pub(crate) fn finish_instruction(&mut self) -> Result<u8, ExecutionError> {
+        let start = self.in_progress.as_ref().map_or(self.cycles, |progress| progress.start.cycles);
+
+        while !self.tick()? {}
+
+        Ok((self.cycles - start) as u8)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            registers: self.registers(),
+            cycles: self.cycles,
+            irq: self.irq,
+            nmi_pending: self.nmi_pending,
+            run_state: self.run_state,
+        }
+    }
+
+    fn restore(&mut self, snapshot: &Snapshot) {
+        self.set_registers(snapshot.registers);
+        self.cycles = snapshot.cycles;
+        self.irq = snapshot.irq;
+        self.nmi_pending = snapshot.nmi_pending;
+        self.run_state = snapshot.run_state;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::{Cmos, CmosProcessor};
+    use crate::processor::core::Processor;
+    use crate::processor::core::interrupts::NMI_VECTOR;
+    use crate::processor::core::undocumented::UnstablePolicy;
+    use crate::processor::nmos::{Nmos, NmosProcessor};
+    use crate::processor::status::Status;
+    use crate::processor::variant::Variant;
+    use crate::processor::Value;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Access {
+        Read(u16),
+        Write(u16, Value),
+    }
+
+    // keeps a log of every access made
+    #[derive(Default)]
+    struct LoggingMemory {
+        memory: VecMemory,
+        log: Vec<Access>,
+        ticks: u64,
+    }
+
+    impl Memory for LoggingMemory {
+        fn peek(&self, address: &Address) -> Value {
+            self.memory.peek(address)
+        }
+
+        fn read(&mut self, address: &Address) -> Value {
+            self.log.push(Access::Read(address.0));
+            self.memory.peek(address)
+        }
+
+        fn write(&mut self, address: &Address, value: &Value) {
+            self.log.push(Access::Write(address.0, *value));
+            self.memory.write(address, value)
+        }
+
+        fn tick(&mut self) {
+            self.ticks += 1;
+        }
+    }
+
+    impl LoggingMemory {
+        fn load(&mut self, start: u16, program: &[u8]) {
+            for (i, byte) in program.iter().enumerate() {
+                self.memory.write(&Address(start + i as u16), byte);
+            }
+        }
+
+        fn take_log(&mut self) -> Vec<Access> {
+            std::mem::take(&mut self.log)
+        }
+    }
+
+    fn assert_one_access_per_cycle<V: Variant>() {
+        for op_code in V::op_codes().iter().chain(V::undocumented_op_codes()) {
+            for (index, status) in [(0x00, 0x00), (0x20, 0xcf)] {
+                let mut memory = LoggingMemory::default();
+                // the operand points at $12F0, the zero page pointer at $30F0 and a
+                // branch goes back onto page one, so an index of $20 crosses a page
+                memory.load(0x0200, &[op_code.execution_metrics.op_code, 0xf0, 0x12]);
+                memory.load(0x00f0, &[0xf0, 0x30]);
+
+                let mut processor = Processor::<_, V>::with_memory(memory);
+                processor.enable_undocumented(UnstablePolicy { enabled: true, ..Default::default() });
+                processor.set_program_counter(0x0200);
+                processor.set_x(index);
+                processor.set_y(index);
+                processor.set_status(Status::from_bits(status));
+
+                let cycles = processor.step().unwrap();
+                let log = processor.bus_mut().take_log();
+                assert_eq!(
+                    log.len(), cycles as usize,
+                    "{:?} {:?} ({:#04x}) with index {:#04x} and status {:#04x}: {:?}",
+                    op_code.instruction, op_code.address_mode, op_code.execution_metrics.op_code, index, status, log,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_access_per_cycle() {
+        assert_one_access_per_cycle::<Nmos>();
+        assert_one_access_per_cycle::<Cmos>();
+    }
+
+    fn accesses<V: Variant>(program: &[u8], index: u8) -> Vec<Access> {
+        let mut memory = LoggingMemory::default();
+        memory.load(0x0200, program);
+        memory.memory.write(&Address(0x3000), &0x41);
+
+        let mut processor = Processor::<_, V>::with_memory(memory);
+        processor.set_program_counter(0x0200);
+        processor.set_x(index);
+        processor.step().unwrap();
+
+        processor.bus_mut().take_log()
+    }
+
+    #[test]
+    fn test_indexed_page_cross() {
+        let program = &[0xBD, 0xf0, 0x12]; // LDA $12F0,X
+
+        // the NMOS part reads the address before the carry, the 65C02 the operand again
+        assert_eq!(accesses::<Nmos>(program, 0x20), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202), Access::Read(0x1210), Access::Read(0x1310),
+        ]);
+        assert_eq!(accesses::<Cmos>(program, 0x20), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202), Access::Read(0x0202), Access::Read(0x1310),
+        ]);
+
+        // within the page a read is made once
+        assert_eq!(accesses::<Nmos>(program, 0x01), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202), Access::Read(0x12f1),
+        ]);
+
+        // a store always reads first
+        let program = &[0x9D, 0xf0, 0x12]; // STA $12F0,X
+        assert_eq!(accesses::<Nmos>(program, 0x01), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202), Access::Read(0x12f1), Access::Write(0x12f1, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_read_modify_write_accesses() {
+        let program = &[0xEE, 0x00, 0x30]; // INC $3000
+
+        assert_eq!(accesses::<Nmos>(program, 0), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202),
+            Access::Read(0x3000), Access::Write(0x3000, 0x41), Access::Write(0x3000, 0x42),
+        ]);
+        assert_eq!(accesses::<Cmos>(program, 0), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202),
+            Access::Read(0x3000), Access::Read(0x3000), Access::Write(0x3000, 0x42),
+        ]);
+    }
+
+    #[test]
+    fn test_shift_indexed_accesses() {
+        // the 65C02 only fixes up the address when indexing crosses a page
+        assert_eq!(accesses::<Cmos>(&[0x1E, 0x00, 0x30], 0), vec![ // ASL $3000,X
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x0202),
+            Access::Read(0x3000), Access::Read(0x3000), Access::Write(0x3000, 0x82),
+        ]);
+
+        let program = &[0x1E, 0xf0, 0x2f]; // ASL $2FF0,X
+        assert_eq!(accesses::<Cmos>(program, 0x05).len(), 6);
+        assert_eq!(accesses::<Cmos>(program, 0x10).len(), 7);
+        assert_eq!(accesses::<Nmos>(program, 0x05).len(), 7);
+
+        // INC always does
+        assert_eq!(accesses::<Cmos>(&[0xFE, 0xf0, 0x2f], 0x05).len(), 7);
+    }
+
+    #[test]
+    fn test_jsr_accesses() {
+        // the high byte of the target is read last
+        assert_eq!(accesses::<Nmos>(&[0x20, 0x00, 0x30], 0), vec![
+            Access::Read(0x0200), Access::Read(0x0201), Access::Read(0x01fd),
+            Access::Write(0x01fd, 0x02), Access::Write(0x01fc, 0x02), Access::Read(0x0202),
+        ]);
+    }
+
+    #[test]
+    fn test_tick() {
+        let mut memory = VecMemory::default();
+        for (i, byte) in [0xAD, 0x00, 0x30, 0x8D, 0x01, 0x30].iter().enumerate() { // LDA $3000, STA $3001
+            memory.write(&Address(0x0200 + i as u16), byte);
+        }
+
+        let mut processor = CmosProcessor::with_memory(memory);
+        processor.set_program_counter(0x0200);
+
+        for _ in 0..3 {
+            assert!(!processor.tick().unwrap());
+        }
+        assert_eq!(processor.cycles(), 3);
+        assert_eq!(processor.program_counter(), 0x0200);
+
+        // the read happens on the last cycle, so it sees a write made after the third
+        processor.bus_mut().write(&Address(0x3000), &0x42);
+        assert!(processor.tick().unwrap());
+        assert_eq!(processor.accumulator(), 0x42);
+        assert_eq!(processor.program_counter(), 0x0203);
+        assert_eq!(processor.cycles(), 4);
+
+        for _ in 0..3 {
+            assert!(!processor.tick().unwrap());
+            assert_eq!(processor.bus().peek(&Address(0x3001)), 0x00);
+        }
+        assert!(processor.tick().unwrap());
+        assert_eq!(processor.bus().peek(&Address(0x3001)), 0x42);
+        assert_eq!(processor.cycles(), 8);
+    }
+
+    #[test]
+    fn test_tick_agrees_with_step() {
+        let program = &[
+            0xA2, 0x03,       // LDX #$03
+            0x20, 0x10, 0x02, // JSR $0210
+            0xCA,             // DEX
+            0xD0, 0xFA,       // BNE back to the JSR
+            0x02,             // not decoded without the undocumented op codes, ends the run
+            0, 0, 0, 0, 0, 0, 0,
+            0xFE, 0xF0, 0x30, // INC $30F0,X
+            0xB1, 0x40,       // LDA ($40),Y
+            0x48,             // PHA
+            0x68,             // PLA
+            0x60,             // RTS
+        ];
+
+        let mut stepped = NmosProcessor::with_memory(LoggingMemory::default());
+        let mut ticked = NmosProcessor::with_memory(LoggingMemory::default());
+
+        for processor in [&mut stepped, &mut ticked] {
+            processor.bus_mut().load(0x0200, program);
+            processor.bus_mut().load(0x0040, &[0xff, 0x30]);
+            processor.set_program_counter(0x0200);
+            processor.set_y(0x10);
+        }
+
+        while stepped.step().is_ok() {}
+        while ticked.tick().is_ok() {}
+
+        assert_eq!(ticked.registers(), stepped.registers());
+        assert_eq!(ticked.cycles(), stepped.cycles());
+        // each access made exactly once, in the same order
+        assert_eq!(ticked.bus_mut().take_log(), stepped.bus_mut().take_log());
+    }
+
+    fn assert_tick_agrees_with_step<V: Variant>() {
+        for op_code in V::op_codes().iter().chain(V::undocumented_op_codes()) {
+            for (index, status) in [(0x00, 0x00), (0x20, 0xcf)] {
+                let mut stepped = Processor::<_, V>::with_memory(LoggingMemory::default());
+                let mut ticked = Processor::<_, V>::with_memory(LoggingMemory::default());
+
+                for processor in [&mut stepped, &mut ticked] {
+                    processor.bus_mut().load(0x0200, &[op_code.execution_metrics.op_code, 0xf0, 0x12]);
+                    processor.bus_mut().load(0x00f0, &[0xf0, 0x30]);
+                    processor.enable_undocumented(UnstablePolicy { enabled: true, ..Default::default() });
+                    processor.set_program_counter(0x0200);
+                    processor.set_x(index);
+                    processor.set_y(index);
+                    processor.set_status(Status::from_bits(status));
+                }
+
+                let step = stepped.step();
+                let mut cycles = 0;
+                let tick = loop {
+                    cycles += 1;
+                    match ticked.tick() {
+                        Ok(false) => continue,
+                        result => break result.map(|_| cycles),
+                    }
+                };
+
+                let context = format!(
+                    "{:?} {:?} ({:#04x}) with index {:#04x} and status {:#04x}",
+                    op_code.instruction, op_code.address_mode, op_code.execution_metrics.op_code, index, status,
+                );
+                assert_eq!(tick, step, "{}", context);
+                assert_eq!(ticked.registers(), stepped.registers(), "{}", context);
+                assert_eq!(ticked.run_state, stepped.run_state, "{}", context);
+                assert_eq!(ticked.cycles(), stepped.cycles(), "{}", context);
+                // nothing reaches the memory but the one access of each cycle
+                assert_eq!(ticked.bus().ticks, stepped.bus().ticks, "{}", context);
+                assert_eq!(ticked.bus_mut().take_log(), stepped.bus_mut().take_log(), "{}", context);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tick_agrees_with_step_for_every_op_code() {
+        assert_tick_agrees_with_step::<Nmos>();
+        assert_tick_agrees_with_step::<Cmos>();
+    }
+
+    #[test]
+    fn test_step_finishes_tick() {
+        let mut memory = VecMemory::default();
+        for (i, byte) in [0xEE, 0x00, 0x30].iter().enumerate() { // INC $3000
+            memory.write(&Address(0x0200 + i as u16), byte);
+        }
+
+        let mut processor = NmosProcessor::with_memory(memory);
+        processor.set_program_counter(0x0200);
+
+        processor.tick().unwrap();
+        processor.tick().unwrap();
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.cycles(), 6);
+        assert_eq!(processor.bus().peek(&Address(0x3000)), 1);
+        assert_eq!(processor.program_counter(), 0x0203);
+    }
+
+    #[test]
+    fn test_tick_nmi() {
+        let mut memory = VecMemory::default();
+        for (i, byte) in [0xEE, 0x00, 0x30].iter().enumerate() { // INC $3000
+            memory.write(&Address(0x0200 + i as u16), byte);
+        }
+        memory.write(&NMI_VECTOR, &0x00);
+        memory.write(&NMI_VECTOR.add_check_page_cross(1).0, &0x40);
+
+        let mut processor = NmosProcessor::with_memory(memory);
+        processor.set_program_counter(0x0200);
+
+        // an edge partway through the instruction is serviced after it
+        processor.tick().unwrap();
+        processor.set_nmi(true);
+        while !processor.tick().unwrap() {}
+        assert_eq!(processor.bus().peek(&Address(0x3000)), 1);
+        assert_eq!(processor.program_counter(), 0x0203);
+
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.program_counter(), 0x4000);
+    }
+}
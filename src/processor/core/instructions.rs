@@ -0,0 +1,1426 @@
+use std::ops::Add;
+use crate::memory::address::{Address, AddressMode};
+use crate::memory::Memory;
+use crate::processor::core::{Processor, RunState};
+use crate::processor::variant::Variant;
+use crate::processor::core::interrupts::IRQ_VECTOR;
+use crate::processor::status::Status;
+use crate::processor::decimal::DecimalResult;
+use crate::processor::{get_bit, ExecutionError, ExecutionMetrics, Register8, Value};
+
+// the 65C02 NOP which takes eight cycles
+const LONG_NOP: u8 = 0x5c;
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    #[inline(always)]
+    pub(super) fn set_zero_flag(&mut self, value: Value){
+        self.status.set_zero(value == 0);
+    }
+
+    #[inline(always)]
+    pub(super) fn set_negative_flag(&mut self, value: Value){
+        self.status.set_negative(get_bit(value, 7));
+    }
+
+    pub(super) fn write_address(&mut self, address_mode: &AddressMode, value: Value) -> Result<(), ExecutionError> {
+        let (address, _) = self.translate_address(address_mode)?;
+        self.fix_up_indexing(address_mode, address, true);
+
+        self.write_bus(address, value);
+
+        Ok(())
+    }
+
+    // applies the operation to the accumulator in accumulator mode, otherwise to the byte in memory,
+    // returning the result. the chip spends a cycle on the operation between the read and the write
+    pub(super) fn modify(&mut self, address_mode: &AddressMode, operation: impl FnOnce(&mut Self, Value) -> Value) -> Result<Value, ExecutionError> {
+        self.modify_indexed(address_mode, true, operation).map(|(value, _)| value)
+    }
+
+    // the shifts and rotates, which on the 65C02 only spend the fix up cycle of abs,X when
+    // indexing crosses a page, returning it as an additional cycle
+    fn shift(&mut self, address_mode: &AddressMode, operation: impl FnOnce(&mut Self, Value) -> Value) -> Result<u8, ExecutionError> {
+        let (_, additional_cycles) = self.modify_indexed(address_mode, !V::SHIFT_INDEXED_PAGE_PENALTY, operation)?;
+        Ok(additional_cycles)
+    }
+
+    fn modify_indexed(
+        &mut self,
+        address_mode: &AddressMode,
+        always_fix_up: bool,
+        operation: impl FnOnce(&mut Self, Value) -> Value,
+    ) -> Result<(Value, u8), ExecutionError> {
+        match address_mode {
+            AddressMode::Accumulator => {
+                let value = self.accumulator;
+                self.accumulator = operation(self, value);
+                Ok((self.accumulator, 0))
+            }
+            _ => {
+                let (address, additional_cycles) = self.translate_address(address_mode)?;
+                self.fix_up_indexing(address_mode, address, always_fix_up);
+                let additional_cycles = if always_fix_up { 0 } else { additional_cycles };
+
+                let value = self.read_bus(address);
+                if V::READ_MODIFY_WRITE_DOUBLE_WRITE {
+                    self.write_bus(address, value);
+                } else {
+                    self.read_bus(address);
+                }
+
+                let value = operation(self, value);
+                self.write_bus(address, value);
+                Ok((value, additional_cycles))
+            }
+        }
+    }
+
+    // implemented as instructed from
+    // https://www.xjavascript.com/blog/6502-emulation-proper-way-to-implement-adc-and-sbc
+    fn add_with_carry(&mut self, value: Value) {
+        let carry = self.status.carry();
+
+        let overflow_value = value & 0b01111111;
+        let overflow_acc = self.accumulator & 0b01111111;
+        // carry into bit 7
+        let overflow_carry_in = (overflow_acc + overflow_value + carry as u8) >> 7;
+
+        let sum = (self.accumulator as u16) + (value as u16) + (carry as u16);
+
+        self.accumulator = (0xff & sum) as Register8;
+
+        let carry_flag = get_bit(sum, 8);
+        self.status.set_carry(carry_flag);
+
+        let overflow_carry_out = carry_flag as u8;
+        let overflow = overflow_carry_in ^ overflow_carry_out;
+        self.status.set_overflow(overflow == 1);
+
+        self.set_zero_flag(self.accumulator);
+        self.set_negative_flag(self.accumulator);
+    }
+
+    fn compare(&mut self, register: Register8, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        let result = register.wrapping_sub(value);
+
+        self.status.set_carry(register >= value);
+        self.set_zero_flag(result);
+        self.set_negative_flag(result);
+
+        Ok(additional_cycles)
+    }
+
+    fn branch(&mut self, condition: bool, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        if !condition {
+            return Ok(0);
+        }
+
+        let (address, additional_cycles) = self.translate_address(address_mode)?;
+
+        // the next op code is read while the target is worked out, and the high byte of
+        // the program counter takes another cycle when the target is on another page
+        self.read_bus(Address(self.program_counter));
+        if additional_cycles > 1 {
+            self.fix_up_read(Address((self.program_counter & 0xff00) | (address.0 & 0x00ff)));
+        }
+
+        self.program_counter = address.0;
+
+        Ok(additional_cycles)
+    }
+
+    // BBR and BBS test a bit of a zero page byte and branch on it
+    fn branch_on_bit(&mut self, bit: usize, set: bool, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let AddressMode::ZeroPageRelative(_, offset) = address_mode else {
+            return Err(ExecutionError::AddressModeMismatch(address_mode.kind()));
+        };
+
+        // the byte is read twice before the branch
+        let (address, _) = self.translate_address(address_mode)?;
+        let value = self.read_bus(address);
+        self.read_bus(address);
+
+        self.branch(get_bit(value, bit) == set, &AddressMode::Relative(*offset))
+    }
+
+    pub(super) fn load(&mut self, address_mode: &AddressMode) -> Result<(Value, u8), ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+
+        Ok((value, additional_cycles))
+    }
+
+    pub(super) fn transfer(&mut self, value: Value) -> Value {
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+
+        value
+    }
+
+    pub(super) fn increment(&mut self, value: Value) -> Value {
+        let value = value.wrapping_add(1);
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    pub(super) fn decrement(&mut self, value: Value) -> Value {
+        let value = value.wrapping_sub(1);
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    pub(super) fn shift_left(&mut self, value: Value) -> Value {
+        self.status.set_carry(get_bit(value, 7));
+
+        let value = value << 1;
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    pub(super) fn shift_right(&mut self, value: Value) -> Value {
+        self.status.set_carry(get_bit(value, 0));
+
+        let value = value >> 1;
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    pub(super) fn rotate_left(&mut self, value: Value) -> Value {
+        let carry = self.status.carry() as u8;
+        self.status.set_carry(get_bit(value, 7));
+
+        let value = (value << 1) | carry;
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    pub(super) fn rotate_right(&mut self, value: Value) -> Value {
+        let carry = self.status.carry() as u8;
+        self.status.set_carry(get_bit(value, 0));
+
+        let value = (value >> 1) | (carry << 7);
+        self.set_zero_flag(value);
+        self.set_negative_flag(value);
+        value
+    }
+
+    // the 65C02 spends its extra decimal cycle reading the next op code
+    fn decimal_cycles(&mut self) -> u8 {
+        for _ in 0..V::DECIMAL_CYCLES {
+            self.read_bus(Address(self.program_counter));
+        }
+
+        V::DECIMAL_CYCLES
+    }
+
+    fn set_decimal_result(&mut self, result: DecimalResult) {
+        self.accumulator = result.value;
+
+        self.status.set_carry(result.carry);
+        self.status.set_zero(result.zero);
+        self.status.set_negative(result.negative);
+        self.status.set_overflow(result.overflow);
+    }
+
+    // the 65C02 takes an extra cycle in decimal mode, in exchange its flags are valid
+    pub(crate) fn execute_adc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        if self.status.decimal() {
+            let carry = self.status.carry();
+            self.set_decimal_result(V::adc_decimal(self.accumulator, value, carry));
+            return Ok(additional_cycles + self.decimal_cycles());
+        }
+
+        self.add_with_carry(value);
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_and(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        self.accumulator &= value;
+
+        self.set_zero_flag(self.accumulator);
+        self.set_negative_flag(self.accumulator);
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_asl(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.shift(address_mode, Self::shift_left)
+    }
+
+    pub(crate) fn execute_bbr(&mut self, bit: usize, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch_on_bit(bit, false, address_mode)
+    }
+
+    pub(crate) fn execute_bbs(&mut self, bit: usize, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch_on_bit(bit, true, address_mode)
+    }
+
+    pub(crate) fn execute_bcc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(!self.status.carry(), address_mode)
+    }
+
+    pub(crate) fn execute_bcs(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(self.status.carry(), address_mode)
+    }
+
+    pub(crate) fn execute_beq(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(self.status.zero(), address_mode)
+    }
+
+    pub(crate) fn execute_bit(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        self.set_zero_flag(self.accumulator & value);
+
+        // the immediate mode has no memory byte to copy bits 7 and 6 from
+        if !matches!(address_mode, AddressMode::Immediate(_)) {
+            self.set_negative_flag(value);
+            self.status.set_overflow(get_bit(value, 6));
+        }
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_bmi(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(self.status.negative(), address_mode)
+    }
+
+    pub(crate) fn execute_bne(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(!self.status.zero(), address_mode)
+    }
+
+    pub(crate) fn execute_bpl(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(!self.status.negative(), address_mode)
+    }
+
+    pub(crate) fn execute_bra(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(true, address_mode)
+    }
+
+    pub(crate) fn execute_brk(&mut self) -> Result<u8, ExecutionError> {
+        // the byte after BRK is skipped, so the return address is BRK + 2
+        let return_address = Address(self.program_counter).add(1u8);
+        self.interrupt(IRQ_VECTOR, return_address, true);
+
+        Ok(0)
+    }
+
+    pub(crate) fn execute_bvc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(!self.status.overflow(), address_mode)
+    }
+
+    pub(crate) fn execute_bvs(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.branch(self.status.overflow(), address_mode)
+    }
+
+    pub(crate) fn execute_clc(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_carry(false);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_cld(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_decimal(false);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_cli(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_interrupt_disable(false);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_clv(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_overflow(false);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_cmp(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.compare(self.accumulator, address_mode)
+    }
+
+    pub(crate) fn execute_cpx(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.compare(self.x, address_mode)
+    }
+
+    pub(crate) fn execute_cpy(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.compare(self.y, address_mode)
+    }
+
+    pub(crate) fn execute_dec(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, Self::decrement)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_dex(&mut self) -> Result<u8, ExecutionError> {
+        self.x = self.decrement(self.x);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_dey(&mut self) -> Result<u8, ExecutionError> {
+        self.y = self.decrement(self.y);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_eor(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        self.accumulator ^= value;
+
+        self.set_zero_flag(self.accumulator);
+        self.set_negative_flag(self.accumulator);
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_inc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, Self::increment)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_inx(&mut self) -> Result<u8, ExecutionError> {
+        self.x = self.increment(self.x);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_iny(&mut self) -> Result<u8, ExecutionError> {
+        self.y = self.increment(self.y);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_jmp(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (address, _) = self.translate_address(address_mode)?;
+
+        self.program_counter = address.0;
+
+        Ok(0)
+    }
+
+    pub(crate) fn execute_jsr(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (address, _) = self.translate_address(address_mode)?;
+
+        // the pushed return address points at the last byte of the JSR
+        self.read_stack();
+        self.push_address(Address(self.program_counter.wrapping_sub(1)));
+
+        self.program_counter = address.0;
+
+        Ok(0)
+    }
+
+    // JSR as fetched by step(), which reads the high byte of the target from the last
+    // byte of the JSR only after pushing the address of that byte
+    pub(crate) fn step_jsr(&mut self, execution_metrics: &ExecutionMetrics) -> Result<u8, ExecutionError> {
+        let address_low = self.read_bus(Address(self.program_counter).add(1u8));
+        self.program_counter = self.program_counter.wrapping_add(2);
+
+        self.read_stack();
+        self.push_address(Address(self.program_counter));
+
+        let address_high = self.read_bus(Address(self.program_counter));
+        self.program_counter = Address::from_bytes(address_low, address_high).0;
+
+        self.cycles += execution_metrics.cycles as u64;
+        Ok(execution_metrics.cycles)
+    }
+
+    pub(crate) fn execute_lda(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.load(address_mode)?;
+        self.accumulator = value;
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_ldx(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.load(address_mode)?;
+        self.x = value;
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_ldy(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.load(address_mode)?;
+        self.y = value;
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_lsr(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.shift(address_mode, Self::shift_right)
+    }
+
+    // the undocumented NOPs still read their operand, and take the page cross cycle
+    pub(crate) fn execute_nop(&mut self, address_mode: &AddressMode, execution_metrics: &ExecutionMetrics) -> Result<u8, ExecutionError> {
+        let (_, additional_cycles) = self.read_address(address_mode)?;
+
+        // the 65C02's $5C carries on reading for another four cycles
+        if let (AddressMode::Absolute(address), LONG_NOP) = (address_mode, execution_metrics.op_code) {
+            for _ in 0..4 {
+                self.read_bus(*address);
+            }
+        }
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_ora(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        self.accumulator |= value;
+
+        self.set_zero_flag(self.accumulator);
+        self.set_negative_flag(self.accumulator);
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_pha(&mut self) -> Result<u8, ExecutionError> {
+        self.push(self.accumulator);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_php(&mut self) -> Result<u8, ExecutionError> {
+        self.push(self.status.pushed(true));
+        Ok(0)
+    }
+
+    pub(crate) fn execute_phx(&mut self) -> Result<u8, ExecutionError> {
+        self.push(self.x);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_phy(&mut self) -> Result<u8, ExecutionError> {
+        self.push(self.y);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_pla(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        let value = self.pull();
+        self.accumulator = self.transfer(value);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_plp(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        self.status = Status::pulled(self.pull());
+        Ok(0)
+    }
+
+    pub(crate) fn execute_plx(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        let value = self.pull();
+        self.x = self.transfer(value);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_ply(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        let value = self.pull();
+        self.y = self.transfer(value);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_rmb(&mut self, bit: usize, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, |_, value| value & !(1 << bit))?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_rol(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.shift(address_mode, Self::rotate_left)
+    }
+
+    pub(crate) fn execute_ror(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.shift(address_mode, Self::rotate_right)
+    }
+
+    pub(crate) fn execute_rti(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        self.status = Status::pulled(self.pull());
+
+        self.program_counter = self.pull_address().0;
+
+        Ok(0)
+    }
+
+    // the pulled address is read while it's incremented
+    pub(crate) fn execute_rts(&mut self) -> Result<u8, ExecutionError> {
+        self.read_stack();
+        let address = self.pull_address();
+        self.read_bus(address);
+
+        self.program_counter = address.0.wrapping_add(1);
+
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sbc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        if self.status.decimal() {
+            let carry = self.status.carry();
+            self.set_decimal_result(V::sbc_decimal(self.accumulator, value, carry));
+            return Ok(additional_cycles + self.decimal_cycles());
+        }
+
+        // subtracting is adding the ones' complement, the carry acts as an inverted borrow
+        self.add_with_carry(!value);
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_sec(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_carry(true);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sed(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_decimal(true);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sei(&mut self) -> Result<u8, ExecutionError> {
+        self.status.set_interrupt_disable(true);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_smb(&mut self, bit: usize, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, |_, value| value | (1 << bit))?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sta(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.write_address(address_mode, self.accumulator)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_stp(&mut self) -> Result<u8, ExecutionError> {
+        self.read_bus(Address(self.program_counter));
+        self.run_state = RunState::Stopped;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_stx(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.write_address(address_mode, self.x)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sty(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.write_address(address_mode, self.y)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_stz(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.write_address(address_mode, 0)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tax(&mut self) -> Result<u8, ExecutionError> {
+        self.x = self.transfer(self.accumulator);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tay(&mut self) -> Result<u8, ExecutionError> {
+        self.y = self.transfer(self.accumulator);
+        Ok(0)
+    }
+
+    // TRB and TSB set zero like BIT does, then clear or set the accumulator's bits in memory
+    pub(crate) fn execute_trb(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, |processor, value| {
+            processor.set_zero_flag(processor.accumulator & value);
+            value & !processor.accumulator
+        })?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tsb(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.modify(address_mode, |processor, value| {
+            processor.set_zero_flag(processor.accumulator & value);
+            value | processor.accumulator
+        })?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tsx(&mut self) -> Result<u8, ExecutionError> {
+        self.x = self.transfer(self.stack_pointer);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_txa(&mut self) -> Result<u8, ExecutionError> {
+        self.accumulator = self.transfer(self.x);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_txs(&mut self) -> Result<u8, ExecutionError> {
+        // the only transfer which doesn't affect the flags
+        self.stack_pointer = self.x;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tya(&mut self) -> Result<u8, ExecutionError> {
+        self.accumulator = self.transfer(self.y);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_wai(&mut self) -> Result<u8, ExecutionError> {
+        self.read_bus(Address(self.program_counter));
+        self.run_state = RunState::Waiting;
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::{Address, AddressMode, ZeroPageAddress};
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::CmosProcessor;
+    use crate::processor::status::{FLAG_BREAK, FLAG_CARRY, FLAG_DECIMAL, FLAG_INTERRUPT_DISABLE, FLAG_NEGATIVE, FLAG_OVERFLOW, FLAG_ZERO};
+    use crate::processor::Instruction;
+
+    #[test]
+    fn test_adc() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // add small value
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(5)).unwrap();
+        assert_eq!(processor.accumulator, 5);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        // bring right to the edge of overflowing
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(250)).unwrap();
+        assert_eq!(processor.accumulator, 255);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        // make sure carry is set after overflow, and acc is modulo 256
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(1)).unwrap();
+        assert_eq!(processor.accumulator, 0);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        // make sure carry is added when set
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(1)).unwrap();
+        assert_eq!(processor.accumulator, 2);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        // reset processor & memory
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(64)).unwrap();
+        assert_eq!(processor.accumulator, 64);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        // test negative value (when thinking in twos compliment signed way),
+        // two positives summing to a negative is a signed overflow
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(64)).unwrap();
+        assert_eq!(processor.accumulator, 128);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        // overflow back to zero
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(128)).unwrap();
+        assert_eq!(processor.accumulator, 0);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_and() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b1100;
+
+        processor.execute(&Instruction::AND, &AddressMode::Immediate(0b1010)).unwrap();
+        assert_eq!(processor.accumulator, 0b1000);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+    }
+
+    #[test]
+    fn test_asl() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b00110000;
+
+        processor.execute(&Instruction::ASL, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0b01100000);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::ASL, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0b11000000);
+
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+
+        processor.execute(&Instruction::ASL, &AddressMode::Accumulator).unwrap();
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        processor.execute(&Instruction::ASL, &AddressMode::Accumulator).unwrap();
+
+        assert_eq!(processor.accumulator, 0b00000000);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(processor.status.get_bit(FLAG_CARRY));
+    }
+
+    #[test]
+    fn test_asl_memory() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b1100_0000);
+        memory.write(&Address(0x1100), &0b0100_0000);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // the flags follow the shifted byte, not the accumulator
+        processor.accumulator = 0x00;
+        processor.execute(&Instruction::ASL, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b1000_0000);
+        assert_eq!(processor.accumulator, 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::ASL, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        // read-modify-write takes the same time whether or not a page is crossed
+        processor.accumulator = 0x01;
+        processor.x = 0x01;
+        let cycles = processor.execute(&Instruction::ASL, &AddressMode::AbsoluteX(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 7);
+        assert_eq!(processor.memory.peek(&Address(0x1100)), 0b1000_0000);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_read_modify_write_memory_flags() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0x01);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        let zero_page = AddressMode::ZeroPage(ZeroPageAddress(0x40));
+
+        // a non-zero, positive accumulator throughout so flags taken from it would show
+        processor.accumulator = 0x7f;
+
+        processor.execute(&Instruction::LSR, &zero_page).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::ROR, &zero_page).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x80);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::ROL, &zero_page).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::DEC, &zero_page).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0xff);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::INC, &zero_page).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        assert_eq!(processor.accumulator, 0x7f);
+    }
+
+    #[test]
+    fn test_index_register_flags() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0x7f;
+
+        processor.x = 0xff;
+        processor.execute(&Instruction::INX, &AddressMode::Implied).unwrap();
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.y = 0x00;
+        processor.execute(&Instruction::DEY, &AddressMode::Implied).unwrap();
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::CPX, &AddressMode::Immediate(0x00)).unwrap();
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        processor.execute(&Instruction::CPY, &AddressMode::Immediate(0x01)).unwrap();
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(processor.status.get_bit(FLAG_CARRY));
+    }
+
+    #[test]
+    fn test_sbc() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        // carry set means no borrow
+        processor.accumulator = 10;
+        processor.execute(&Instruction::SEC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::SBC, &AddressMode::Immediate(3)).unwrap();
+        assert_eq!(processor.accumulator, 7);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        // carry clear borrows one
+        processor.execute(&Instruction::CLC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::SBC, &AddressMode::Immediate(6)).unwrap();
+        assert_eq!(processor.accumulator, 0);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        // going below zero clears carry
+        processor.execute(&Instruction::SBC, &AddressMode::Immediate(1)).unwrap();
+        assert_eq!(processor.accumulator, 0xff);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+
+        // -128 - 1 overflows
+        processor.accumulator = 0x80;
+        processor.execute(&Instruction::SEC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::SBC, &AddressMode::Immediate(1)).unwrap();
+        assert_eq!(processor.accumulator, 0x7f);
+        assert!(processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_logical() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b1100;
+
+        processor.execute(&Instruction::ORA, &AddressMode::Immediate(0b1000_0011)).unwrap();
+        assert_eq!(processor.accumulator, 0b1000_1111);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::EOR, &AddressMode::Immediate(0b1000_1111)).unwrap();
+        assert_eq!(processor.accumulator, 0);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_bit() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0020), &0b1100_0000);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b0011_1111;
+
+        processor.execute(&Instruction::BIT, &AddressMode::ZeroPage(ZeroPageAddress(0x20))).unwrap();
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(processor.status.get_bit(FLAG_OVERFLOW));
+        // the accumulator is left untouched
+        assert_eq!(processor.accumulator, 0b0011_1111);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0x40;
+        processor.x = 0x40;
+        processor.y = 0x01;
+
+        processor.execute(&Instruction::CMP, &AddressMode::Immediate(0x30)).unwrap();
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::CPX, &AddressMode::Immediate(0x40)).unwrap();
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::CPY, &AddressMode::Immediate(0x02)).unwrap();
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_load_store() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::LDA, &AddressMode::Immediate(0x80)).unwrap();
+        assert_eq!(processor.accumulator, 0x80);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::LDX, &AddressMode::Immediate(0x00)).unwrap();
+        assert_eq!(processor.x, 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::LDY, &AddressMode::Immediate(0x12)).unwrap();
+        assert_eq!(processor.y, 0x12);
+
+        processor.execute(&Instruction::STA, &AddressMode::Absolute(Address(0x1000))).unwrap();
+        processor.execute(&Instruction::STX, &AddressMode::ZeroPage(ZeroPageAddress(0x10))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x1000)), 0x80);
+        assert_eq!(processor.memory.peek(&Address(0x0010)), 0x00);
+
+        processor.x = 0x02;
+        processor.execute(&Instruction::STY, &AddressMode::ZeroPageX(ZeroPageAddress(0x10))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0012)), 0x12);
+    }
+
+    #[test]
+    fn test_store_cycles_ignore_page_cross() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x01;
+
+        let cycles = processor.execute(&Instruction::STA, &AddressMode::AbsoluteX(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 5);
+
+        let cycles = processor.execute(&Instruction::LDA, &AddressMode::AbsoluteX(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 5);
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.accumulator = 0x81;
+        processor.execute(&Instruction::TAX, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::TAY, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.x, 0x81);
+        assert_eq!(processor.y, 0x81);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.x = 0x00;
+        processor.execute(&Instruction::TXS, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.stack_pointer, 0x00);
+        // TXS leaves the flags alone
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::TXA, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.accumulator, 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::TYA, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.accumulator, 0x81);
+
+        processor.stack_pointer = 0xfd;
+        processor.execute(&Instruction::TSX, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.x, 0xfd);
+    }
+
+    #[test]
+    fn test_increment_decrement() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0xff);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::INC, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::DEC, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0xff);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::DEX, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.x, 0xff);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::INX, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.x, 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::INY, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.y, 0x01);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        processor.execute(&Instruction::DEY, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.y, 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+    }
+
+    #[test]
+    fn test_shift_rotate() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b1000_0001);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.accumulator = 0b0000_0011;
+        processor.execute(&Instruction::LSR, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0b0000_0001);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        // carry rotates into bit 0, bit 7 into carry
+        processor.execute(&Instruction::ROL, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_0011);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+
+        // carry rotates into bit 7, bit 0 into carry
+        processor.accumulator = 0b0000_0010;
+        processor.execute(&Instruction::ROR, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0b1000_0001);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::LSR, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_0001);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::SEC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::SED, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::SEI, &AddressMode::Implied).unwrap();
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_DECIMAL));
+        assert!(processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+
+        processor.execute(&Instruction::CLC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::CLD, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::CLI, &AddressMode::Implied).unwrap();
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_DECIMAL));
+        assert!(!processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+
+        processor.status.enable_bit(FLAG_OVERFLOW);
+        processor.execute(&Instruction::CLV, &AddressMode::Implied).unwrap();
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+    }
+
+    #[test]
+    fn test_branch_not_taken() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+        processor.status.enable_bit(FLAG_CARRY);
+
+        let cycles = processor.execute(&Instruction::BCC, &AddressMode::Relative(0x10)).unwrap();
+        assert_eq!(cycles, 2);
+        assert_eq!(processor.program_counter, 0x0202);
+    }
+
+    #[test]
+    fn test_jmp() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        let cycles = processor.execute(&Instruction::JMP, &AddressMode::Absolute(Address(0x1234))).unwrap();
+        assert_eq!(cycles, 3);
+        assert_eq!(processor.program_counter, 0x1234);
+    }
+
+    #[test]
+    fn test_stack() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.stack_pointer = 0xff;
+
+        processor.accumulator = 0x42;
+        processor.execute(&Instruction::PHA, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.stack_pointer, 0xfe);
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0x42);
+
+        processor.status.enable_bit(FLAG_CARRY);
+        processor.execute(&Instruction::PHP, &AddressMode::Implied).unwrap();
+        // break and unused bits are set on the pushed copy
+        assert_eq!(processor.memory.peek(&Address(0x01fe)), 0b0011_0001);
+
+        processor.execute(&Instruction::CLC, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::PLP, &AddressMode::Implied).unwrap();
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_BREAK));
+
+        processor.accumulator = 0x00;
+        processor.execute(&Instruction::PLA, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.accumulator, 0x42);
+        assert_eq!(processor.stack_pointer, 0xff);
+    }
+
+    #[test]
+    fn test_jsr_rts() {
+        let mut memory = VecMemory::default();
+        // JSR $1000 at $0200, RTS at $1000
+        memory.write(&Address(0x0200), &0x20);
+        memory.write(&Address(0x0201), &0x00);
+        memory.write(&Address(0x0202), &0x10);
+        memory.write(&Address(0x1000), &0x60);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.stack_pointer = 0xff;
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.program_counter, 0x1000);
+        // return address is the last byte of the JSR instruction
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0x02);
+        assert_eq!(processor.memory.peek(&Address(0x01fe)), 0x02);
+
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.program_counter, 0x0203);
+        assert_eq!(processor.stack_pointer, 0xff);
+    }
+
+    #[test]
+    fn test_brk_rti() {
+        let mut memory = VecMemory::default();
+        // BRK at $0200, RTI at the handler
+        memory.write(&Address(0x0200), &0x00);
+        memory.write(&Address(0xfffe), &0x00);
+        memory.write(&Address(0xffff), &0x30);
+        memory.write(&Address(0x3000), &0x40);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.stack_pointer = 0xff;
+        processor.program_counter = 0x0200;
+        processor.status.enable_bit(FLAG_DECIMAL);
+
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.program_counter, 0x3000);
+        assert!(processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+        assert!(!processor.status.get_bit(FLAG_DECIMAL));
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0x02);
+        assert_eq!(processor.memory.peek(&Address(0x01fe)), 0x02);
+        assert_eq!(processor.memory.peek(&Address(0x01fd)), 0b0011_1000);
+
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.program_counter, 0x0202);
+        assert!(!processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+        assert!(processor.status.get_bit(FLAG_DECIMAL));
+        assert!(!processor.status.get_bit(FLAG_BREAK));
+    }
+
+    #[test]
+    fn test_nop() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        let cycles = processor.execute(&Instruction::NOP, &AddressMode::Implied).unwrap();
+        assert_eq!(cycles, 2);
+        assert_eq!(processor.program_counter, 0x0001);
+    }
+
+    #[test]
+    fn test_decimal() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.execute(&Instruction::SED, &AddressMode::Implied).unwrap();
+
+        processor.accumulator = 0x05;
+        let cycles = processor.execute(&Instruction::ADC, &AddressMode::Immediate(0x05)).unwrap();
+        assert_eq!(cycles, 3);
+        assert_eq!(processor.accumulator, 0x10);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+
+        processor.accumulator = 0x99;
+        processor.execute(&Instruction::ADC, &AddressMode::Immediate(0x01)).unwrap();
+        assert_eq!(processor.accumulator, 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.accumulator = 0x46;
+        let cycles = processor.execute(&Instruction::SBC, &AddressMode::Immediate(0x12)).unwrap();
+        assert_eq!(cycles, 3);
+        assert_eq!(processor.accumulator, 0x34);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        processor.execute(&Instruction::SBC, &AddressMode::Immediate(0x35)).unwrap();
+        assert_eq!(processor.accumulator, 0x99);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        // binary mode is back to the usual cycle count
+        processor.execute(&Instruction::CLD, &AddressMode::Implied).unwrap();
+        let cycles = processor.execute(&Instruction::ADC, &AddressMode::Immediate(0x05)).unwrap();
+        assert_eq!(cycles, 2);
+    }
+
+    #[test]
+    fn test_stz() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x1234), &0xff);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0x42;
+
+        processor.execute(&Instruction::STZ, &AddressMode::Absolute(Address(0x1234))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x1234)), 0x00);
+        assert_eq!(processor.accumulator, 0x42);
+    }
+
+    #[test]
+    fn test_trb_tsb() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b1100_1100);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b0000_0011;
+
+        // no bits in common
+        processor.execute(&Instruction::TSB, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b1100_1111);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+
+        processor.accumulator = 0b1000_0001;
+        processor.execute(&Instruction::TRB, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0100_1110);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+        assert_eq!(processor.accumulator, 0b1000_0001);
+    }
+
+    #[test]
+    fn test_push_pull_index() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.x = 0x12;
+        processor.y = 0x80;
+
+        processor.execute(&Instruction::PHX, &AddressMode::Implied).unwrap();
+        processor.execute(&Instruction::PHY, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.stack_pointer, 0xfb);
+
+        processor.execute(&Instruction::PLX, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.x, 0x80);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::PLY, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.y, 0x12);
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_increment_decrement_accumulator() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.execute(&Instruction::DEC, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0xff);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.execute(&Instruction::INC, &AddressMode::Accumulator).unwrap();
+        assert_eq!(processor.accumulator, 0x00);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+    }
+
+    #[test]
+    fn test_bit_immediate() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.accumulator = 0b0000_0001;
+
+        // only zero is affected
+        processor.execute(&Instruction::BIT, &AddressMode::Immediate(0b1100_0000)).unwrap();
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(!processor.status.get_bit(FLAG_NEGATIVE));
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+    }
+
+    #[test]
+    fn test_rmb_smb() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b1010_1010);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        let cycles = processor.execute(&Instruction::RMB7, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0010_1010);
+
+        processor.execute(&Instruction::SMB0, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0010_1011);
+
+        // flags are untouched
+        processor.execute(&Instruction::RMB5, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_1011);
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+    }
+
+    #[test]
+    fn test_bbr_bbs() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b0000_0100);
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x1000;
+
+        // bit 2 is set, so BBR2 falls through
+        let cycles = processor.execute(&Instruction::BBR2, &AddressMode::ZeroPageRelative(ZeroPageAddress(0x40), 0x10)).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.program_counter, 0x1003);
+
+        // taken, relative to the next instruction
+        let cycles = processor.execute(&Instruction::BBS2, &AddressMode::ZeroPageRelative(ZeroPageAddress(0x40), 0x10)).unwrap();
+        assert_eq!(cycles, 6);
+        assert_eq!(processor.program_counter, 0x1016);
+
+        // taken backwards across a page
+        let cycles = processor.execute(&Instruction::BBR3, &AddressMode::ZeroPageRelative(ZeroPageAddress(0x40), -0x20)).unwrap();
+        assert_eq!(cycles, 7);
+        assert_eq!(processor.program_counter, 0x0ff9);
+    }
+
+    #[test]
+    fn test_shift_indexed_cycles() {
+        let mut memory = VecMemory::default();
+        let program: &[u8] = &[
+            0x1E, 0xf0, 0x2f, // ASL $2FF0,X
+            0x1E, 0xf0, 0x2f, // ASL $2FF0,X
+            0xFE, 0xf0, 0x2f, // INC $2FF0,X
+        ];
+        for (i, byte) in program.iter().enumerate() {
+            memory.write(&Address(0x0200 + i as u16), byte);
+        }
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        // the 65C02 only takes the cycle fixing up the address when indexing crosses a page
+        processor.x = 0x05;
+        assert_eq!(processor.step().unwrap(), 6);
+        processor.x = 0x10;
+        assert_eq!(processor.step().unwrap(), 7);
+
+        // INC always does
+        processor.x = 0x05;
+        assert_eq!(processor.step().unwrap(), 7);
+    }
+}
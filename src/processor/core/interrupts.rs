@@ -0,0 +1,215 @@
+use std::ops::Add;
+use crate::memory::address::Address;
+use crate::memory::Memory;
+use crate::processor::core::{Processor, RunState};
+use crate::processor::variant::Variant;
+
+pub const NMI_VECTOR: Address = Address(0xfffa);
+pub const RESET_VECTOR: Address = Address(0xfffc);
+pub const IRQ_VECTOR: Address = Address(0xfffe);
+
+// reset and the interrupt sequences all take seven cycles
+pub(crate) const INTERRUPT_CYCLES: u8 = 7;
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    // runs the reset sequence and jumps through the reset vector. like the hardware
+    // the three stack pushes are suppressed, turned into reads, but the stack pointer
+    // still moves down. an instruction tick() started is abandoned
+    pub fn reset(&mut self) -> u8 {
+        self.in_progress = None;
+
+        self.read_bus(Address(self.program_counter));
+        self.read_bus(Address(self.program_counter));
+        for _ in 0..3 {
+            self.read_stack();
+            self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+        }
+
+        self.status.set_interrupt_disable(true);
+        if V::INTERRUPT_CLEARS_DECIMAL {
+            self.status.set_decimal(false);
+        }
+
+        self.program_counter = self.read_vector(RESET_VECTOR).0;
+        self.nmi_pending = false;
+        self.run_state = RunState::Running;
+
+        self.cycles += INTERRUPT_CYCLES as u64;
+        INTERRUPT_CYCLES
+    }
+
+    // the irq line is level triggered, it is serviced before every instruction
+    // for as long as it stays asserted and interrupts aren't disabled
+    pub fn set_irq(&mut self, asserted: bool) {
+        self.irq = asserted;
+    }
+
+    // the nmi line is edge triggered, only going from released to asserted
+    // latches an interrupt
+    pub fn set_nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi {
+            self.nmi_pending = true;
+        }
+
+        self.nmi = asserted;
+    }
+
+    // services a pending interrupt, returning the cycles taken if there was one. the
+    // op code is read twice and thrown away before the sequence BRK shares
+    pub(crate) fn poll_interrupts(&mut self) -> Option<u8> {
+        let vector = if self.nmi_pending {
+            self.nmi_pending = false;
+            NMI_VECTOR
+        } else if self.irq && !self.status.interrupt_disable() {
+            IRQ_VECTOR
+        } else {
+            return None;
+        };
+
+        self.read_bus(Address(self.program_counter));
+        self.read_bus(Address(self.program_counter));
+        self.interrupt(vector, Address(self.program_counter), false);
+
+        self.cycles += INTERRUPT_CYCLES as u64;
+        Some(INTERRUPT_CYCLES)
+    }
+
+    // shared by BRK, IRQ and NMI. the break bit only exists on the pushed copy
+    // of the status register and tells BRK apart from a hardware interrupt
+    pub(crate) fn interrupt(&mut self, vector: Address, return_address: Address, break_flag: bool) {
+        self.push_address(return_address);
+
+        self.push(self.status.pushed(break_flag));
+
+        self.status.set_interrupt_disable(true);
+        if V::INTERRUPT_CLEARS_DECIMAL {
+            self.status.set_decimal(false);
+        }
+
+        self.program_counter = self.read_vector(vector).0;
+    }
+
+    fn read_vector(&mut self, vector: Address) -> Address {
+        let low = self.read_bus(vector);
+        let high = self.read_bus(vector.add(1u8));
+        Address::from_bytes(low, high)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::CmosProcessor;
+    use crate::processor::status::{FLAG_DECIMAL, FLAG_INTERRUPT_DISABLE};
+
+    // NOPs at $0200, an RTI handler at $3000 for IRQ and $4000 for NMI
+    fn memory() -> VecMemory {
+        let mut memory = VecMemory::default();
+        for address in 0x0200..0x0210 {
+            memory.write(&Address(address), &0xea);
+        }
+        memory.write(&Address(0x3000), &0x40);
+        memory.write(&Address(0x4000), &0x40);
+
+        memory.write(&Address(0xfffa), &0x00);
+        memory.write(&Address(0xfffb), &0x40);
+        memory.write(&Address(0xfffc), &0x00);
+        memory.write(&Address(0xfffd), &0x02);
+        memory.write(&Address(0xfffe), &0x00);
+        memory.write(&Address(0xffff), &0x30);
+        memory
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut memory = memory();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.status.enable_bit(FLAG_DECIMAL);
+        processor.stack_pointer = 0x00;
+
+        assert_eq!(processor.reset(), 7);
+        assert_eq!(processor.program_counter, 0x0200);
+        assert_eq!(processor.stack_pointer, 0xfd);
+        assert!(processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+        assert!(!processor.status.get_bit(FLAG_DECIMAL));
+        assert_eq!(processor.cycles, 7);
+
+        // nothing is written to the stack
+        assert_eq!(processor.memory.peek(&Address(0x0100)), 0x00);
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0x00);
+        assert_eq!(processor.memory.peek(&Address(0x01fe)), 0x00);
+    }
+
+    #[test]
+    fn test_irq() {
+        let mut memory = memory();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.reset();
+
+        // masked by the interrupt disable flag after reset
+        processor.set_irq(true);
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.program_counter, 0x0201);
+
+        processor.status.clear_bit(FLAG_INTERRUPT_DISABLE);
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.program_counter, 0x3000);
+        assert!(processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+
+        // return address and status with the break bit clear
+        assert_eq!(processor.memory.peek(&Address(0x01fa)), 0x02);
+        assert_eq!(processor.memory.peek(&Address(0x01f9)), 0x01);
+        assert_eq!(processor.memory.peek(&Address(0x01f8)), 0b0010_0000);
+
+        // the line is still asserted so the interrupt is taken again after RTI
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.program_counter, 0x0201);
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.program_counter, 0x3000);
+
+        processor.set_irq(false);
+        processor.step().unwrap();
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.program_counter, 0x0202);
+    }
+
+    #[test]
+    fn test_nmi() {
+        let mut memory = memory();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.reset();
+
+        // not masked by the interrupt disable flag
+        processor.set_nmi(true);
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.program_counter, 0x4000);
+
+        // holding the line doesn't trigger another one
+        processor.step().unwrap();
+        assert_eq!(processor.program_counter, 0x0200);
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.program_counter, 0x0201);
+
+        // a new edge does
+        processor.set_nmi(false);
+        processor.set_nmi(true);
+        processor.step().unwrap();
+        assert_eq!(processor.program_counter, 0x4000);
+    }
+
+    #[test]
+    fn test_nmi_before_irq() {
+        let mut memory = memory();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.reset();
+        processor.status.clear_bit(FLAG_INTERRUPT_DISABLE);
+
+        processor.set_irq(true);
+        processor.set_nmi(true);
+        processor.step().unwrap();
+        assert_eq!(processor.program_counter, 0x4000);
+    }
+}
@@ -0,0 +1,692 @@
+use std::marker::PhantomData;
+use crate::memory::address::{Address, AddressMode, AddressModeKind};
+use crate::processor::{ExecutionError, ExecutionMetrics, Instruction, Registers};
+use crate::memory::Memory;
+use crate::processor::{Register16, Register8};
+use crate::processor::op_codes::OpCode;
+use crate::processor::status::Status;
+use crate::processor::variant::Variant;
+use clock::{InProgress, Replay};
+use stack::POWER_ON_STACK_POINTER;
+use undocumented::UnstablePolicy;
+
+mod addressing;
+mod clock;
+pub mod instructions;
+pub mod interrupts;
+mod stack;
+pub mod undocumented;
+
+// WAI leaves the processor waiting for an interrupt line to assert,
+// STP leaves it stopped and JAM leaves it jammed until the next reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunState {
+    Running,
+    Waiting,
+    Stopped,
+    Jammed,
+}
+
+// the processor core shared by the variants, which supply the op code tables
+// and the quirks of their chip
+pub struct Processor<M: Memory, V: Variant> {
+    pub(crate) program_counter: Register16,
+    pub(crate) accumulator: Register8,
+    pub(crate) x: Register8,
+    pub(crate) y: Register8,
+    pub(crate) status: Status,
+    pub(crate) stack_pointer: Register8,
+    pub(crate) memory: M,
+    pub(crate) cycles: u64,
+    pub(crate) irq: bool,
+    pub(crate) nmi: bool,
+    pub(crate) nmi_pending: bool,
+    pub(crate) run_state: RunState,
+    pub(crate) undocumented: Option<UnstablePolicy>,
+    pub(crate) replay: Replay,
+    pub(crate) in_progress: Option<InProgress>,
+    variant: PhantomData<V>,
+}
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+    pub fn with_memory(memory: M) -> Self {
+        Self {
+            program_counter: 0,
+            memory,
+            x: 0,
+            y: 0,
+            status: Default::default(),
+            accumulator: 0,
+            stack_pointer: POWER_ON_STACK_POINTER,
+            cycles: 0,
+            irq: false,
+            nmi: false,
+            nmi_pending: false,
+            run_state: RunState::Running,
+            undocumented: None,
+            replay: Replay::default(),
+            in_progress: None,
+            variant: PhantomData,
+        }
+    }
+
+
+    pub fn bus(&self) -> &M {
+        &self.memory
+    }
+
+    pub fn bus_mut(&mut self) -> &mut M {
+        &mut self.memory
+    }
+
+    pub fn into_bus(self) -> M {
+        self.memory
+    }
+
+    pub fn run_state(&self) -> RunState {
+        self.run_state
+    }
+
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub fn program_counter(&self) -> Register16 {
+        self.program_counter
+    }
+
+    pub fn set_program_counter(&mut self, value: Register16) {
+        self.program_counter = value;
+    }
+
+    pub fn accumulator(&self) -> Register8 {
+        self.accumulator
+    }
+
+    pub fn set_accumulator(&mut self, value: Register8) {
+        self.accumulator = value;
+    }
+
+    pub fn x(&self) -> Register8 {
+        self.x
+    }
+
+    pub fn set_x(&mut self, value: Register8) {
+        self.x = value;
+    }
+
+    pub fn y(&self) -> Register8 {
+        self.y
+    }
+
+    pub fn set_y(&mut self, value: Register8) {
+        self.y = value;
+    }
+
+    pub fn stack_pointer(&self) -> Register8 {
+        self.stack_pointer
+    }
+
+    pub fn set_stack_pointer(&mut self, value: Register8) {
+        self.stack_pointer = value;
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn status_mut(&mut self) -> &mut Status {
+        &mut self.status
+    }
+
+    pub fn set_status(&mut self, status: Status) {
+        self.status = status;
+    }
+
+    pub fn registers(&self) -> Registers {
+        Registers {
+            program_counter: self.program_counter,
+            accumulator: self.accumulator,
+            x: self.x,
+            y: self.y,
+            stack_pointer: self.stack_pointer,
+            status: self.status,
+        }
+    }
+
+    pub fn set_registers(&mut self, registers: Registers) {
+        self.program_counter = registers.program_counter;
+        self.accumulator = registers.accumulator;
+        self.x = registers.x;
+        self.y = registers.y;
+        self.stack_pointer = registers.stack_pointer;
+        self.status = registers.status;
+    }
+
+    // fetches, decodes and executes the instruction at the program counter,
+    // returning the number of cycles it took. a pending interrupt is serviced
+    // instead of the instruction. while waiting the clock still runs, so a step
+    // idles for a single cycle, once stopped or jammed only a reset gets it going.
+    // an instruction tick() started is run to the end
+    pub fn step(&mut self) -> Result<u8, ExecutionError> {
+        if self.in_progress.is_some() {
+            return self.finish_instruction();
+        }
+
+        let result = self.run_instruction();
+        self.check_write_fault(result)
+    }
+
+    // a write the memory refused fails the step, or tick, it happened in
+    pub(crate) fn check_write_fault<T>(&mut self, result: Result<T, ExecutionError>) -> Result<T, ExecutionError> {
+        match self.memory.take_write_fault() {
+            Some((address, value)) => Err(ExecutionError::WriteToRom { address: address.0, value }),
+            None => result,
+        }
+    }
+
+    pub(crate) fn run_instruction(&mut self) -> Result<u8, ExecutionError> {
+        match self.run_state {
+            RunState::Running => {}
+            RunState::Stopped => return Err(ExecutionError::Stopped),
+            RunState::Jammed => return Err(ExecutionError::Jammed { address: self.program_counter }),
+            RunState::Waiting => {
+                // an irq wakes the processor even when it's masked, execution then
+                // simply carries on after the WAI
+                if !self.irq && !self.nmi_pending {
+                    return Ok(self.idle());
+                }
+                self.run_state = RunState::Running;
+            }
+        }
+
+        if let Some(cycles) = self.poll_interrupts() {
+            return Ok(cycles);
+        }
+
+        let op_code = self.read_bus(Address(self.program_counter));
+
+        let Some(op_code) = self.decode(op_code) else {
+            return Err(ExecutionError::InvalidOpCode { op_code, address: self.program_counter });
+        };
+
+        // JSR only reads the high byte of its target after pushing the return address
+        if op_code.instruction == Instruction::JSR {
+            return self.step_jsr(&op_code.execution_metrics);
+        }
+
+        let address_mode = self.read_operand(op_code);
+
+        self.execute_with_metrics(&op_code.instruction, &address_mode, &op_code.execution_metrics)
+    }
+
+    fn decode(&self, op_code: u8) -> Option<&'static OpCode> {
+        V::decode(op_code).or_else(|| {
+            let policy = self.undocumented?;
+            V::decode_undocumented(op_code).filter(|op_code| policy.enabled || !undocumented::is_unstable(&op_code.instruction))
+        })
+    }
+
+    // the metrics execute runs an instruction with, held to the same opt in as decode
+    fn execution_metrics(&self, instruction: &Instruction, address_mode: &AddressModeKind) -> Option<ExecutionMetrics> {
+        V::execution_metrics(instruction, address_mode).or_else(|| {
+            let policy = self.undocumented?;
+            if !policy.enabled && undocumented::is_unstable(instruction) {
+                return None;
+            }
+            V::undocumented_execution_metrics(instruction, address_mode)
+        })
+    }
+
+    fn idle(&mut self) -> u8 {
+        self.read_bus(Address(self.program_counter));
+        self.cycles += 1;
+        1
+    }
+
+    pub fn execute(&mut self, instruction: &Instruction, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let Some(execution_metrics) = self.execution_metrics(instruction, &address_mode.kind()) else {
+            return Err(ExecutionError::IllegalAddressMode { instruction: *instruction, address_mode: address_mode.kind() });
+        };
+
+        self.execute_with_metrics(instruction, address_mode, &execution_metrics)
+    }
+
+    fn execute_with_metrics(
+        &mut self,
+        instruction: &Instruction,
+        address_mode: &AddressMode,
+        execution_metrics: &ExecutionMetrics,
+    ) -> Result<u8, ExecutionError> {
+        // instructions see the program counter pointing at the next instruction
+        self.program_counter = self.program_counter.wrapping_add(execution_metrics.bytes as u16);
+
+        let additional_cycles = match instruction {
+            Instruction::ADC => self.execute_adc(address_mode)?,
+            Instruction::ALR => self.execute_alr(address_mode)?,
+            Instruction::ANC => self.execute_anc(address_mode)?,
+            Instruction::AND => self.execute_and(address_mode)?,
+            Instruction::ANE => self.execute_ane(address_mode)?,
+            Instruction::ARR => self.execute_arr(address_mode)?,
+            Instruction::ASL => self.execute_asl(address_mode)?,
+            Instruction::BBR0 => self.execute_bbr(0, address_mode)?,
+            Instruction::BBR1 => self.execute_bbr(1, address_mode)?,
+            Instruction::BBR2 => self.execute_bbr(2, address_mode)?,
+            Instruction::BBR3 => self.execute_bbr(3, address_mode)?,
+            Instruction::BBR4 => self.execute_bbr(4, address_mode)?,
+            Instruction::BBR5 => self.execute_bbr(5, address_mode)?,
+            Instruction::BBR6 => self.execute_bbr(6, address_mode)?,
+            Instruction::BBR7 => self.execute_bbr(7, address_mode)?,
+            Instruction::BBS0 => self.execute_bbs(0, address_mode)?,
+            Instruction::BBS1 => self.execute_bbs(1, address_mode)?,
+            Instruction::BBS2 => self.execute_bbs(2, address_mode)?,
+            Instruction::BBS3 => self.execute_bbs(3, address_mode)?,
+            Instruction::BBS4 => self.execute_bbs(4, address_mode)?,
+            Instruction::BBS5 => self.execute_bbs(5, address_mode)?,
+            Instruction::BBS6 => self.execute_bbs(6, address_mode)?,
+            Instruction::BBS7 => self.execute_bbs(7, address_mode)?,
+            Instruction::BCC => self.execute_bcc(address_mode)?,
+            Instruction::BCS => self.execute_bcs(address_mode)?,
+            Instruction::BEQ => self.execute_beq(address_mode)?,
+            Instruction::BIT => self.execute_bit(address_mode)?,
+            Instruction::BMI => self.execute_bmi(address_mode)?,
+            Instruction::BNE => self.execute_bne(address_mode)?,
+            Instruction::BPL => self.execute_bpl(address_mode)?,
+            Instruction::BRA => self.execute_bra(address_mode)?,
+            Instruction::BRK => self.execute_brk()?,
+            Instruction::BVC => self.execute_bvc(address_mode)?,
+            Instruction::BVS => self.execute_bvs(address_mode)?,
+            Instruction::CLC => self.execute_clc()?,
+            Instruction::CLD => self.execute_cld()?,
+            Instruction::CLI => self.execute_cli()?,
+            Instruction::CLV => self.execute_clv()?,
+            Instruction::CMP => self.execute_cmp(address_mode)?,
+            Instruction::CPX => self.execute_cpx(address_mode)?,
+            Instruction::CPY => self.execute_cpy(address_mode)?,
+            Instruction::DCP => self.execute_dcp(address_mode)?,
+            Instruction::DEC => self.execute_dec(address_mode)?,
+            Instruction::DEX => self.execute_dex()?,
+            Instruction::DEY => self.execute_dey()?,
+            Instruction::EOR => self.execute_eor(address_mode)?,
+            Instruction::INC => self.execute_inc(address_mode)?,
+            Instruction::INX => self.execute_inx()?,
+            Instruction::INY => self.execute_iny()?,
+            Instruction::ISC => self.execute_isc(address_mode)?,
+            Instruction::JAM => self.execute_jam()?,
+            Instruction::JMP => self.execute_jmp(address_mode)?,
+            Instruction::JSR => self.execute_jsr(address_mode)?,
+            Instruction::LAS => self.execute_las(address_mode)?,
+            Instruction::LAX => self.execute_lax(address_mode)?,
+            Instruction::LDA => self.execute_lda(address_mode)?,
+            Instruction::LDX => self.execute_ldx(address_mode)?,
+            Instruction::LDY => self.execute_ldy(address_mode)?,
+            Instruction::LSR => self.execute_lsr(address_mode)?,
+            Instruction::LXA => self.execute_lxa(address_mode)?,
+            Instruction::NOP => self.execute_nop(address_mode, execution_metrics)?,
+            Instruction::ORA => self.execute_ora(address_mode)?,
+            Instruction::PHA => self.execute_pha()?,
+            Instruction::PHP => self.execute_php()?,
+            Instruction::PHX => self.execute_phx()?,
+            Instruction::PHY => self.execute_phy()?,
+            Instruction::PLA => self.execute_pla()?,
+            Instruction::PLP => self.execute_plp()?,
+            Instruction::PLX => self.execute_plx()?,
+            Instruction::PLY => self.execute_ply()?,
+            Instruction::RLA => self.execute_rla(address_mode)?,
+            Instruction::RMB0 => self.execute_rmb(0, address_mode)?,
+            Instruction::RMB1 => self.execute_rmb(1, address_mode)?,
+            Instruction::RMB2 => self.execute_rmb(2, address_mode)?,
+            Instruction::RMB3 => self.execute_rmb(3, address_mode)?,
+            Instruction::RMB4 => self.execute_rmb(4, address_mode)?,
+            Instruction::RMB5 => self.execute_rmb(5, address_mode)?,
+            Instruction::RMB6 => self.execute_rmb(6, address_mode)?,
+            Instruction::RMB7 => self.execute_rmb(7, address_mode)?,
+            Instruction::ROL => self.execute_rol(address_mode)?,
+            Instruction::ROR => self.execute_ror(address_mode)?,
+            Instruction::RRA => self.execute_rra(address_mode)?,
+            Instruction::RTI => self.execute_rti()?,
+            Instruction::RTS => self.execute_rts()?,
+            Instruction::SAX => self.execute_sax(address_mode)?,
+            Instruction::SBC => self.execute_sbc(address_mode)?,
+            Instruction::SBX => self.execute_sbx(address_mode)?,
+            Instruction::SEC => self.execute_sec()?,
+            Instruction::SED => self.execute_sed()?,
+            Instruction::SEI => self.execute_sei()?,
+            Instruction::SHA => self.execute_sha(address_mode)?,
+            Instruction::SHX => self.execute_shx(address_mode)?,
+            Instruction::SHY => self.execute_shy(address_mode)?,
+            Instruction::SLO => self.execute_slo(address_mode)?,
+            Instruction::SMB0 => self.execute_smb(0, address_mode)?,
+            Instruction::SMB1 => self.execute_smb(1, address_mode)?,
+            Instruction::SMB2 => self.execute_smb(2, address_mode)?,
+            Instruction::SMB3 => self.execute_smb(3, address_mode)?,
+            Instruction::SMB4 => self.execute_smb(4, address_mode)?,
+            Instruction::SMB5 => self.execute_smb(5, address_mode)?,
+            Instruction::SMB6 => self.execute_smb(6, address_mode)?,
+            Instruction::SMB7 => self.execute_smb(7, address_mode)?,
+            Instruction::SRE => self.execute_sre(address_mode)?,
+            Instruction::STA => self.execute_sta(address_mode)?,
+            Instruction::STP => self.execute_stp()?,
+            Instruction::STX => self.execute_stx(address_mode)?,
+            Instruction::STY => self.execute_sty(address_mode)?,
+            Instruction::STZ => self.execute_stz(address_mode)?,
+            Instruction::TAS => self.execute_tas(address_mode)?,
+            Instruction::TAX => self.execute_tax()?,
+            Instruction::TAY => self.execute_tay()?,
+            Instruction::TRB => self.execute_trb(address_mode)?,
+            Instruction::TSB => self.execute_tsb(address_mode)?,
+            Instruction::TSX => self.execute_tsx()?,
+            Instruction::TXA => self.execute_txa()?,
+            Instruction::TXS => self.execute_txs()?,
+            Instruction::TYA => self.execute_tya()?,
+            Instruction::WAI => self.execute_wai()?,
+        };
+
+        let cycles = execution_metrics.cycles + additional_cycles;
+
+        self.cycles += cycles as u64;
+
+        Ok(cycles)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::{Address, AddressMode, AddressModeKind};
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::CmosProcessor;
+    use crate::processor::core::RunState;
+    use crate::processor::nmos::NmosProcessor;
+    use crate::processor::status::{Status, FLAG_INTERRUPT_DISABLE};
+    use crate::processor::{ExecutionError, Instruction, Registers};
+
+    fn load(memory: &mut VecMemory, start: u16, program: &[u8]) {
+        for (i, byte) in program.iter().enumerate() {
+            memory.write(&Address(start + i as u16), byte);
+        }
+    }
+
+    #[test]
+    fn test_step() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0x69, 0x05,       // ADC #$05
+            0x6D, 0x34, 0x12, // ADC $1234
+            0x29, 0x0C,       // AND #$0C
+            0x0A,             // ASL A
+        ]);
+        memory.write(&Address(0x1234), &0x0a);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.accumulator, 0x05);
+        assert_eq!(processor.program_counter, 0x0202);
+
+        // operand is read little-endian
+        assert_eq!(processor.step().unwrap(), 4);
+        assert_eq!(processor.accumulator, 0x0f);
+        assert_eq!(processor.program_counter, 0x0205);
+
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.accumulator, 0x0c);
+        assert_eq!(processor.program_counter, 0x0207);
+
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.accumulator, 0x18);
+        assert_eq!(processor.program_counter, 0x0208);
+
+        assert_eq!(processor.cycles, 10);
+    }
+
+    #[test]
+    fn test_step_page_cross_cycles() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0x7D, 0xff, 0x10, // ADC $10ff,X
+        ]);
+        memory.write(&Address(0x1100), &0x07);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+        processor.x = 1;
+
+        assert_eq!(processor.step().unwrap(), 5);
+        assert_eq!(processor.accumulator, 0x07);
+    }
+
+    #[test]
+    fn test_step_branch_loop() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xA2, 0x03, // LDX #$03
+            0xCA,       // DEX
+            0xD0, 0xfd, // BNE -3
+            0xEA,       // NOP
+        ]);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 2);
+        for _ in 0..2 {
+            assert_eq!(processor.step().unwrap(), 2);
+            assert_eq!(processor.step().unwrap(), 3);
+            assert_eq!(processor.program_counter, 0x0202);
+        }
+
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.program_counter, 0x0205);
+        assert_eq!(processor.x, 0);
+    }
+
+    #[test]
+    fn test_step_zero_page_relative() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0x8F, 0x40, 0x7e, // BBS0 $40,+126
+        ]);
+        memory.write(&Address(0x0040), &0x01);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 6);
+        assert_eq!(processor.program_counter, 0x0281);
+    }
+
+    #[test]
+    fn test_step_undefined_nops() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0x03,             // 1 byte, 1 cycle
+            0x42, 0xff,       // 2 bytes, 2 cycles
+            0x5C, 0x34, 0x12, // 3 bytes, 8 cycles
+            0xFC, 0xff, 0x12, // 3 bytes, 4 cycles
+        ]);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+        processor.x = 0x01;
+
+        assert_eq!(processor.step().unwrap(), 1);
+        assert_eq!(processor.program_counter, 0x0201);
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.program_counter, 0x0203);
+        assert_eq!(processor.step().unwrap(), 8);
+        assert_eq!(processor.program_counter, 0x0206);
+        assert_eq!(processor.step().unwrap(), 4);
+        assert_eq!(processor.program_counter, 0x0209);
+
+        assert_eq!(processor.accumulator, 0x00);
+        assert_eq!(processor.x, 0x01);
+    }
+
+    #[test]
+    fn test_step_invalid_op_code() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0x02, // JAM, undocumented on the NMOS part
+        ]);
+
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step(), Err(ExecutionError::InvalidOpCode { op_code: 0x02, address: 0x0200 }));
+        assert_eq!(processor.program_counter, 0x0200);
+        assert_eq!(processor.cycles, 0);
+    }
+
+    #[test]
+    fn test_execute_illegal_address_mode() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        assert_eq!(
+            processor.execute(&Instruction::STA, &AddressMode::Immediate(0x01)),
+            Err(ExecutionError::IllegalAddressMode { instruction: Instruction::STA, address_mode: AddressModeKind::Immediate })
+        );
+    }
+
+    #[test]
+    fn test_wai() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xCB, // WAI
+            0xEA, // NOP
+        ]);
+        memory.write(&Address(0xfffe), &0x00);
+        memory.write(&Address(0xffff), &0x30);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 3);
+        assert_eq!(processor.run_state(), RunState::Waiting);
+
+        // idles until an interrupt line asserts
+        assert_eq!(processor.step().unwrap(), 1);
+        assert_eq!(processor.step().unwrap(), 1);
+        assert_eq!(processor.program_counter, 0x0201);
+
+        processor.set_irq(true);
+        assert_eq!(processor.step().unwrap(), 7);
+        assert_eq!(processor.run_state(), RunState::Running);
+        assert_eq!(processor.program_counter, 0x3000);
+        assert_eq!(processor.cycles, 12);
+    }
+
+    #[test]
+    fn test_wai_masked_irq() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xCB, // WAI
+            0xEA, // NOP
+        ]);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+        processor.status.enable_bit(FLAG_INTERRUPT_DISABLE);
+
+        processor.step().unwrap();
+        processor.set_irq(true);
+
+        // wakes without servicing the interrupt and carries on with the NOP
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.run_state(), RunState::Running);
+        assert_eq!(processor.program_counter, 0x0202);
+    }
+
+    #[test]
+    fn test_stp() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xDB, // STP
+        ]);
+        memory.write(&Address(0xfffc), &0x00);
+        memory.write(&Address(0xfffd), &0x04);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 3);
+        assert_eq!(processor.run_state(), RunState::Stopped);
+
+        // interrupts don't wake a stopped processor, only reset does
+        processor.set_irq(true);
+        processor.set_nmi(true);
+        assert_eq!(processor.step(), Err(ExecutionError::Stopped));
+        assert_eq!(processor.program_counter, 0x0201);
+
+        processor.reset();
+        assert_eq!(processor.run_state(), RunState::Running);
+        assert_eq!(processor.program_counter, 0x0400);
+    }
+
+    #[test]
+    fn test_registers() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xA9, 0x80, // LDA #$80
+            0xAA,       // TAX
+        ]);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.set_program_counter(0x0200);
+        processor.set_y(0x12);
+
+        processor.step().unwrap();
+        processor.step().unwrap();
+
+        let registers = processor.registers();
+        assert_eq!(registers, Registers {
+            program_counter: 0x0203,
+            accumulator: 0x80,
+            x: 0x80,
+            y: 0x12,
+            stack_pointer: 0xfd,
+            status: Status::from_bits(0b1000_0000),
+        });
+        assert!(processor.status().negative());
+        assert_eq!(processor.cycles(), 4);
+
+        processor.set_accumulator(0x00);
+        processor.status_mut().set_carry(true);
+        assert_eq!(processor.accumulator(), 0x00);
+        assert!(processor.status().carry());
+
+        processor.set_registers(registers);
+        assert_eq!(processor.registers(), registers);
+        assert_eq!(processor.accumulator(), 0x80);
+        assert_eq!(processor.x(), 0x80);
+        assert_eq!(processor.stack_pointer(), 0xfd);
+    }
+
+    #[test]
+    fn test_owned_bus() {
+        let mut memory = VecMemory::default();
+        load(&mut memory, 0x0200, &[
+            0xA9, 0x42,       // LDA #$42
+            0x8D, 0x00, 0x30, // STA $3000
+        ]);
+
+        let mut processor = CmosProcessor::with_memory(memory);
+        processor.set_program_counter(0x0200);
+        processor.bus_mut().write(&Address(0x3001), &0x24);
+
+        // owning its memory, the processor can be moved like any other value
+        let processor = std::thread::spawn(move || {
+            processor.step().unwrap();
+            processor.step().unwrap();
+            processor
+        }).join().unwrap();
+        assert_eq!(processor.bus().peek(&Address(0x3000)), 0x42);
+
+        let memory = processor.into_bus();
+        assert_eq!(memory.peek(&Address(0x3000)), 0x42);
+        assert_eq!(memory.peek(&Address(0x3001)), 0x24);
+    }
+}
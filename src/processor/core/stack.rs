@@ -0,0 +1,109 @@
+use crate::memory::address::Address;
+use crate::memory::Memory;
+use crate::processor::core::Processor;
+use crate::processor::variant::Variant;
+use crate::processor::Value;
+
+// the stack lives on page one, the stack pointer is the low byte
+pub(crate) const STACK_PAGE: u16 = 0x0100;
+
+// the stack pointer after the reset sequence has run on a freshly powered chip,
+// the three suppressed pushes move it down from $00
+pub(crate) const POWER_ON_STACK_POINTER: u8 = 0xfd;
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    fn stack_address(&self) -> Address {
+        Address(STACK_PAGE | self.stack_pointer as u16)
+    }
+
+    // the stack grows downwards, the pointer wraps around within page one
+    pub(crate) fn push(&mut self, value: Value) {
+        self.write_bus(self.stack_address(), value);
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+    }
+
+    pub(crate) fn pull(&mut self) -> Value {
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+        self.read_bus(self.stack_address())
+    }
+
+    // the read of the top of the stack made while the stack pointer is moved for a pull
+    pub(crate) fn read_stack(&mut self) {
+        self.read_bus(self.stack_address());
+    }
+
+    // high byte first, so the address reads little-endian in memory
+    pub(crate) fn push_address(&mut self, address: Address) {
+        self.push((address.0 >> 8) as Value);
+        self.push(address.0 as Value);
+    }
+
+    pub(crate) fn pull_address(&mut self) -> Address {
+        let low = self.pull();
+        let high = self.pull();
+        Address::from_bytes(low, high)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::CmosProcessor;
+
+    #[test]
+    fn test_power_on_stack_pointer() {
+        let mut memory = VecMemory::default();
+        let processor = CmosProcessor::with_memory(&mut memory);
+        assert_eq!(processor.stack_pointer, 0xfd);
+    }
+
+    #[test]
+    fn test_push_pull() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+
+        processor.push(0x12);
+        processor.push(0x34);
+        assert_eq!(processor.stack_pointer, 0xfb);
+        assert_eq!(processor.memory.peek(&Address(0x01fd)), 0x12);
+        assert_eq!(processor.memory.peek(&Address(0x01fc)), 0x34);
+
+        assert_eq!(processor.pull(), 0x34);
+        assert_eq!(processor.pull(), 0x12);
+        assert_eq!(processor.stack_pointer, 0xfd);
+    }
+
+    #[test]
+    fn test_push_pull_address() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.stack_pointer = 0xff;
+
+        processor.push_address(Address(0xcabd));
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0xca);
+        assert_eq!(processor.memory.peek(&Address(0x01fe)), 0xbd);
+
+        assert_eq!(processor.pull_address().0, 0xcabd);
+        assert_eq!(processor.stack_pointer, 0xff);
+    }
+
+    #[test]
+    fn test_wraparound() {
+        let mut memory = VecMemory::default();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.stack_pointer = 0x00;
+
+        // pushing at $00 wraps to $ff without leaving page one
+        processor.push_address(Address(0xcabd));
+        assert_eq!(processor.stack_pointer, 0xfe);
+        assert_eq!(processor.memory.peek(&Address(0x0100)), 0xca);
+        assert_eq!(processor.memory.peek(&Address(0x01ff)), 0xbd);
+        assert_eq!(processor.memory.peek(&Address(0x0200)), 0x00);
+
+        assert_eq!(processor.pull_address().0, 0xcabd);
+        assert_eq!(processor.stack_pointer, 0x00);
+    }
+}
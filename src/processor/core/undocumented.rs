@@ -0,0 +1,524 @@
+use crate::memory::address::{Address, AddressMode};
+use crate::memory::Memory;
+use crate::processor::core::{Processor, RunState};
+use crate::processor::variant::Variant;
+use crate::processor::{get_bit, ExecutionError, Instruction, Value};
+
+// how the NMOS op codes whose behaviour differs from chip to chip are emulated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnstablePolicy {
+    // whether ANE, LXA, SHA, SHX, SHY and TAS are decoded at all
+    pub enabled: bool,
+
+    // ANE and LXA or the accumulator with this before masking, usually $EE or $FF
+    pub magic: Value,
+
+    // whether SHA, SHX, SHY and TAS replace the high byte of the target address
+    // with the stored value when indexing crosses a page
+    pub page_cross_corruption: bool,
+}
+
+impl Default for UnstablePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            magic: 0xee,
+            page_cross_corruption: true,
+        }
+    }
+}
+
+pub(crate) fn is_unstable(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::ANE | Instruction::LXA | Instruction::SHA | Instruction::SHX | Instruction::SHY | Instruction::TAS
+    )
+}
+
+impl<M: Memory, V: Variant> Processor<M, V> {
+
+    // decodes the undocumented op codes of the variant from now on, the unstable ones
+    // only if the policy enables them
+    pub fn enable_undocumented(&mut self, policy: UnstablePolicy) {
+        self.undocumented = Some(policy);
+    }
+
+    fn unstable_policy(&self) -> UnstablePolicy {
+        self.undocumented.unwrap_or_default()
+    }
+
+    // SHA, SHX, SHY and TAS store the value anded with the high byte of the base address
+    // plus one. when indexing crosses a page the chip puts the stored value on the high
+    // byte of the address bus as well
+    fn store_high_byte_and(&mut self, value: Value, address_mode: &AddressMode) -> Result<(), ExecutionError> {
+        let (address, _) = self.translate_address(address_mode)?;
+        self.fix_up_indexing(address_mode, address, true);
+
+        let index = match address_mode {
+            AddressMode::AbsoluteX(_) => self.x,
+            _ => self.y,
+        };
+
+        let base_high = (address.0.wrapping_sub(index as u16) >> 8) as u8;
+        let value = value & base_high.wrapping_add(1);
+
+        let page_crossed = (address.0 >> 8) as u8 != base_high;
+        let address = if page_crossed && self.unstable_policy().page_cross_corruption {
+            Address((address.0 & 0x00ff) | ((value as u16) << 8))
+        } else {
+            address
+        };
+
+        self.write_bus(address, value);
+
+        Ok(())
+    }
+
+    pub(crate) fn execute_alr(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, _) = self.read_address(address_mode)?;
+        self.accumulator = self.shift_right(self.accumulator & value);
+        Ok(0)
+    }
+
+    pub(crate) fn execute_anc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let additional_cycles = self.execute_and(address_mode)?;
+        self.status.set_carry(get_bit(self.accumulator, 7));
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_ane(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, _) = self.read_address(address_mode)?;
+        let magic = self.unstable_policy().magic;
+        self.accumulator = self.transfer((self.accumulator | magic) & self.x & value);
+        Ok(0)
+    }
+
+    // the decimal flag changes the result and the flags in the same odd way as ADC
+    pub(crate) fn execute_arr(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, _) = self.read_address(address_mode)?;
+
+        let and = self.accumulator & value;
+        let carry = self.status.carry() as u8;
+        let mut result = (and >> 1) | (carry << 7);
+
+        self.status.set_zero(result == 0);
+        self.status.set_negative(carry == 1);
+
+        if self.status.decimal() {
+            self.status.set_overflow(get_bit(and ^ result, 6));
+
+            if (and & 0x0f) + (and & 0x01) > 5 {
+                result = (result & 0xf0) | (result.wrapping_add(6) & 0x0f);
+            }
+
+            let high_fixup = (and as u16 & 0xf0) + (and as u16 & 0x10) > 0x50;
+            if high_fixup {
+                result = result.wrapping_add(0x60);
+            }
+            self.status.set_carry(high_fixup);
+        } else {
+            self.status.set_carry(get_bit(result, 6));
+            self.status.set_overflow(get_bit(result, 6) ^ get_bit(result, 5));
+        }
+
+        self.accumulator = result;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_dcp(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::decrement)?;
+        self.execute_cmp(&AddressMode::Immediate(value))?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_isc(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::increment)?;
+        self.execute_sbc(&AddressMode::Immediate(value))
+    }
+
+    // the program counter stays on the JAM, the processor keeps fetching it until reset
+    pub(crate) fn execute_jam(&mut self) -> Result<u8, ExecutionError> {
+        self.program_counter = self.program_counter.wrapping_sub(1);
+        self.run_state = RunState::Jammed;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_las(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.read_address(address_mode)?;
+
+        let value = self.transfer(value & self.stack_pointer);
+        self.accumulator = value;
+        self.x = value;
+        self.stack_pointer = value;
+
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_lax(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, additional_cycles) = self.load(address_mode)?;
+        self.accumulator = value;
+        self.x = value;
+        Ok(additional_cycles)
+    }
+
+    pub(crate) fn execute_lxa(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, _) = self.read_address(address_mode)?;
+        let magic = self.unstable_policy().magic;
+
+        let value = self.transfer((self.accumulator | magic) & value);
+        self.accumulator = value;
+        self.x = value;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_rla(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::rotate_left)?;
+        self.execute_and(&AddressMode::Immediate(value))?;
+        Ok(0)
+    }
+
+    // the carry out of the rotation goes into the addition
+    pub(crate) fn execute_rra(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::rotate_right)?;
+        self.execute_adc(&AddressMode::Immediate(value))
+    }
+
+    pub(crate) fn execute_sax(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.write_address(address_mode, self.accumulator & self.x)?;
+        Ok(0)
+    }
+
+    // compares rather than subtracts, so neither the carry nor decimal mode go in
+    pub(crate) fn execute_sbx(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let (value, _) = self.read_address(address_mode)?;
+
+        let and = self.accumulator & self.x;
+        self.status.set_carry(and >= value);
+        self.x = self.transfer(and.wrapping_sub(value));
+
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sha(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.store_high_byte_and(self.accumulator & self.x, address_mode)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_shx(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.store_high_byte_and(self.x, address_mode)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_shy(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.store_high_byte_and(self.y, address_mode)?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_slo(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::shift_left)?;
+        self.execute_ora(&AddressMode::Immediate(value))?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_sre(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        let value = self.modify(address_mode, Self::shift_right)?;
+        self.execute_eor(&AddressMode::Immediate(value))?;
+        Ok(0)
+    }
+
+    pub(crate) fn execute_tas(&mut self, address_mode: &AddressMode) -> Result<u8, ExecutionError> {
+        self.stack_pointer = self.accumulator & self.x;
+        self.store_high_byte_and(self.stack_pointer, address_mode)?;
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::{Address, AddressMode, AddressModeKind, ZeroPageAddress};
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::core::RunState;
+    use crate::processor::core::undocumented::UnstablePolicy;
+    use crate::processor::nmos::NmosProcessor;
+    use crate::processor::status::{FLAG_CARRY, FLAG_DECIMAL, FLAG_NEGATIVE, FLAG_OVERFLOW, FLAG_ZERO};
+    use crate::processor::{ExecutionError, Instruction};
+
+    #[test]
+    fn test_decode_opt_in() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+
+        assert!(processor.decode(0xa7).is_none());
+
+        processor.enable_undocumented(UnstablePolicy::default());
+        assert_eq!(processor.decode(0xa7).unwrap().instruction, Instruction::LAX);
+        assert!(processor.decode(0x8b).is_none());
+
+        processor.enable_undocumented(UnstablePolicy { enabled: true, ..UnstablePolicy::default() });
+        assert_eq!(processor.decode(0x8b).unwrap().instruction, Instruction::ANE);
+    }
+
+    #[test]
+    fn test_execute_opt_in() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+
+        let ane = processor.execute(&Instruction::ANE, &AddressMode::Immediate(0xff));
+        assert_eq!(ane, Err(ExecutionError::IllegalAddressMode { instruction: Instruction::ANE, address_mode: AddressModeKind::Immediate }));
+        let lax = processor.execute(&Instruction::LAX, &AddressMode::ZeroPage(ZeroPageAddress(0x40)));
+        assert_eq!(lax, Err(ExecutionError::IllegalAddressMode { instruction: Instruction::LAX, address_mode: AddressModeKind::ZeroPage }));
+
+        // the stable ones run once enabled, the unstable ones only once the policy enables them too
+        processor.enable_undocumented(UnstablePolicy::default());
+        assert!(processor.execute(&Instruction::LAX, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).is_ok());
+        assert!(processor.execute(&Instruction::ANE, &AddressMode::Immediate(0xff)).is_err());
+
+        processor.enable_undocumented(UnstablePolicy { enabled: true, ..UnstablePolicy::default() });
+        assert!(processor.execute(&Instruction::ANE, &AddressMode::Immediate(0xff)).is_ok());
+    }
+
+    #[test]
+    fn test_read_modify_write_combinations() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0040), &0b1000_0001);
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+
+        // SLO shifts into carry, then ors
+        processor.accumulator = 0b0000_1000;
+        let cycles = processor.execute(&Instruction::SLO, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_0010);
+        assert_eq!(processor.accumulator, 0b0000_1010);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        // RLA rotates the carry in, then ands
+        processor.accumulator = 0b0000_0111;
+        processor.execute(&Instruction::RLA, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_0101);
+        assert_eq!(processor.accumulator, 0b0000_0101);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+
+        // SRE shifts right, then exclusive ors
+        processor.accumulator = 0b1000_0000;
+        processor.execute(&Instruction::SRE, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0b0000_0010);
+        assert_eq!(processor.accumulator, 0b1000_0010);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        // RRA adds with the carry out of the rotation: $02 -> $81, carry clear
+        processor.accumulator = 0x01;
+        processor.execute(&Instruction::RRA, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x81);
+        assert_eq!(processor.accumulator, 0x82);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+
+        // DCP decrements, then compares
+        processor.accumulator = 0x80;
+        processor.execute(&Instruction::DCP, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x80);
+        assert!(processor.status.get_bit(FLAG_ZERO));
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        // ISC increments, then subtracts
+        processor.accumulator = 0x90;
+        processor.execute(&Instruction::ISC, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0040)), 0x81);
+        assert_eq!(processor.accumulator, 0x0f);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+    }
+
+    #[test]
+    fn test_read_modify_write_cycles() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+        processor.y = 0xff;
+
+        // no page cross penalty, the timing is fixed
+        assert_eq!(processor.execute(&Instruction::DCP, &AddressMode::AbsoluteY(Address(0x1001))).unwrap(), 7);
+        assert_eq!(processor.execute(&Instruction::ISC, &AddressMode::PostIndexedIndirectY(ZeroPageAddress(0x10))).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_lax_sax() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x1100), &0x8f);
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+        processor.y = 0x01;
+
+        let cycles = processor.execute(&Instruction::LAX, &AddressMode::AbsoluteY(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.accumulator, 0x8f);
+        assert_eq!(processor.x, 0x8f);
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        processor.x = 0xf1;
+        processor.execute(&Instruction::SAX, &AddressMode::ZeroPage(ZeroPageAddress(0x20))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0020)), 0x81);
+    }
+
+    #[test]
+    fn test_immediate() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+
+        // ANC copies negative into carry
+        processor.accumulator = 0xf0;
+        processor.execute(&Instruction::ANC, &AddressMode::Immediate(0x81)).unwrap();
+        assert_eq!(processor.accumulator, 0x80);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        // ALR ands then shifts
+        processor.accumulator = 0xff;
+        processor.execute(&Instruction::ALR, &AddressMode::Immediate(0x03)).unwrap();
+        assert_eq!(processor.accumulator, 0x01);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        // SBX subtracts from A and X without borrow
+        processor.accumulator = 0x0f;
+        processor.x = 0xfc;
+        processor.status.clear_bit(FLAG_CARRY);
+        processor.execute(&Instruction::SBX, &AddressMode::Immediate(0x02)).unwrap();
+        assert_eq!(processor.x, 0x0a);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+
+        processor.execute(&Instruction::SBX, &AddressMode::Immediate(0x0d)).unwrap();
+        assert_eq!(processor.x, 0xfd);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+    }
+
+    #[test]
+    fn test_arr() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+
+        // binary, carry from bit 6 and overflow from bit 6 xor bit 5
+        processor.accumulator = 0xff;
+        processor.status.enable_bit(FLAG_CARRY);
+        processor.execute(&Instruction::ARR, &AddressMode::Immediate(0xc0)).unwrap();
+        assert_eq!(processor.accumulator, 0xe0);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_OVERFLOW));
+        assert!(processor.status.get_bit(FLAG_NEGATIVE));
+
+        // decimal, the nybbles are fixed up
+        processor.accumulator = 0xff;
+        processor.status.clear_bit(FLAG_CARRY);
+        processor.status.enable_bit(FLAG_DECIMAL);
+        processor.execute(&Instruction::ARR, &AddressMode::Immediate(0x45)).unwrap();
+        assert_eq!(processor.accumulator, 0x28);
+        assert!(!processor.status.get_bit(FLAG_CARRY));
+        assert!(processor.status.get_bit(FLAG_OVERFLOW));
+
+        processor.accumulator = 0xff;
+        processor.execute(&Instruction::ARR, &AddressMode::Immediate(0x99)).unwrap();
+        assert_eq!(processor.accumulator, 0xa2);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+    }
+
+    #[test]
+    fn test_las() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x2000), &0x3c);
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+        processor.stack_pointer = 0xf5;
+
+        processor.execute(&Instruction::LAS, &AddressMode::AbsoluteY(Address(0x2000))).unwrap();
+        assert_eq!(processor.accumulator, 0x34);
+        assert_eq!(processor.x, 0x34);
+        assert_eq!(processor.stack_pointer, 0x34);
+    }
+
+    #[test]
+    fn test_magic_constant() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy { enabled: true, magic: 0xff, ..UnstablePolicy::default() });
+
+        processor.accumulator = 0x00;
+        processor.x = 0x0f;
+        processor.execute(&Instruction::ANE, &AddressMode::Immediate(0x3c)).unwrap();
+        assert_eq!(processor.accumulator, 0x0c);
+
+        processor.enable_undocumented(UnstablePolicy { enabled: true, magic: 0xee, ..UnstablePolicy::default() });
+        processor.accumulator = 0x00;
+        processor.execute(&Instruction::LXA, &AddressMode::Immediate(0x3f)).unwrap();
+        assert_eq!(processor.accumulator, 0x2e);
+        assert_eq!(processor.x, 0x2e);
+    }
+
+    #[test]
+    fn test_store_high_byte() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy { enabled: true, ..UnstablePolicy::default() });
+        processor.x = 0x0f;
+        processor.y = 0x10;
+
+        // anded with the high byte of the base plus one
+        processor.execute(&Instruction::SHX, &AddressMode::AbsoluteY(Address(0x1200))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x1210)), 0x03);
+
+        // crossing a page puts the value on the high byte of the address
+        processor.y = 0x20;
+        processor.execute(&Instruction::SHX, &AddressMode::AbsoluteY(Address(0x12f0))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x0310)), 0x03);
+        assert_eq!(processor.memory.peek(&Address(0x1310)), 0x00);
+
+        processor.enable_undocumented(UnstablePolicy { enabled: true, page_cross_corruption: false, ..UnstablePolicy::default() });
+        processor.execute(&Instruction::SHX, &AddressMode::AbsoluteY(Address(0x12f0))).unwrap();
+        assert_eq!(processor.memory.peek(&Address(0x1310)), 0x03);
+
+        // TAS also sets the stack pointer
+        processor.accumulator = 0xf3;
+        processor.y = 0x00;
+        processor.execute(&Instruction::TAS, &AddressMode::AbsoluteY(Address(0x3000))).unwrap();
+        assert_eq!(processor.stack_pointer, 0x03);
+        assert_eq!(processor.memory.peek(&Address(0x3000)), 0x01);
+    }
+
+    #[test]
+    fn test_jam() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0200), &0x02);
+        memory.write(&Address(0xfffc), &0x00);
+        memory.write(&Address(0xfffd), &0x04);
+
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+        processor.program_counter = 0x0200;
+
+        assert_eq!(processor.step().unwrap(), 2);
+        assert_eq!(processor.run_state(), RunState::Jammed);
+        assert_eq!(processor.program_counter, 0x0200);
+
+        // only reset gets it going again
+        processor.set_nmi(true);
+        assert_eq!(processor.step(), Err(ExecutionError::Jammed { address: 0x0200 }));
+        assert_eq!(processor.program_counter, 0x0200);
+
+        processor.reset();
+        assert_eq!(processor.run_state(), RunState::Running);
+        assert_eq!(processor.program_counter, 0x0400);
+    }
+
+    #[test]
+    fn test_nop_reads_operand() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.enable_undocumented(UnstablePolicy::default());
+        processor.x = 0x01;
+
+        let cycles = processor.execute(&Instruction::NOP, &AddressMode::AbsoluteX(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.accumulator, 0x00);
+    }
+}
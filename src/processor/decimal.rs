@@ -0,0 +1,262 @@
+use crate::processor::Value;
+
+// binary coded decimal arithmetic for ADC and SBC, following the sequences in
+// http://www.6502.org/tutorials/decimal_mode.html which also define the results
+// for invalid (non-bcd) inputs and the flags on each chip
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalResult {
+    pub value: Value,
+    pub carry: bool,
+    pub zero: bool,
+    pub negative: bool,
+    pub overflow: bool,
+}
+
+// sequences 1 and 2, shared by both chips. returns the adjusted sum, the carry
+// and the intermediate sum the NMOS part takes N and V from
+fn add(a: Value, b: Value, carry: bool) -> (Value, bool, i16) {
+    let mut low = (a & 0x0f) as i16 + (b & 0x0f) as i16 + carry as i16;
+    if low >= 0x0a {
+        low = ((low + 0x06) & 0x0f) + 0x10;
+    }
+
+    let mut sum = (a & 0xf0) as i16 + (b & 0xf0) as i16 + low;
+    let signed_sum = (a & 0xf0) as i8 as i16 + (b & 0xf0) as i8 as i16 + low;
+
+    if sum >= 0xa0 {
+        sum += 0x60;
+    }
+
+    (sum as Value, sum >= 0x100, signed_sum)
+}
+
+// the binary subtraction, SBC takes C and V from it on both chips
+fn subtract_binary(a: Value, b: Value, carry: bool) -> (Value, bool, bool) {
+    let difference = a as i16 - b as i16 - (!carry) as i16;
+    let value = difference as Value;
+    let overflow = (a ^ b) & (a ^ value) & 0x80 != 0;
+
+    (value, difference >= 0, overflow)
+}
+
+// N and V come from the sum before the high nibble is adjusted and Z from the
+// binary sum, so only C is valid
+pub(crate) fn adc_nmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+    let (value, carry_out, signed_sum) = add(a, b, carry);
+
+    DecimalResult {
+        value,
+        carry: carry_out,
+        zero: a.wrapping_add(b).wrapping_add(carry as Value) == 0,
+        negative: signed_sum & 0x80 != 0,
+        overflow: !(-128..=127).contains(&signed_sum),
+    }
+}
+
+// N and Z are valid, V is the same as on the NMOS part
+pub(crate) fn adc_cmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+    let (value, carry_out, signed_sum) = add(a, b, carry);
+
+    DecimalResult {
+        value,
+        carry: carry_out,
+        zero: value == 0,
+        negative: value & 0x80 != 0,
+        overflow: !(-128..=127).contains(&signed_sum),
+    }
+}
+
+// sequence 3, all flags are those of the binary subtraction
+pub(crate) fn sbc_nmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+    let mut low = (a & 0x0f) as i16 - (b & 0x0f) as i16 + carry as i16 - 1;
+    if low < 0 {
+        low = ((low - 0x06) & 0x0f) - 0x10;
+    }
+
+    let mut difference = (a & 0xf0) as i16 - (b & 0xf0) as i16 + low;
+    if difference < 0 {
+        difference -= 0x60;
+    }
+
+    let (binary, carry_out, overflow) = subtract_binary(a, b, carry);
+
+    DecimalResult {
+        value: difference as Value,
+        carry: carry_out,
+        zero: binary == 0,
+        negative: binary & 0x80 != 0,
+        overflow,
+    }
+}
+
+// sequence 4, N and Z are valid, C and V are those of the binary subtraction
+pub(crate) fn sbc_cmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+    let low = (a & 0x0f) as i16 - (b & 0x0f) as i16 + carry as i16 - 1;
+
+    let mut difference = a as i16 - b as i16 + carry as i16 - 1;
+    if difference < 0 {
+        difference -= 0x60;
+    }
+    if low < 0 {
+        difference -= 0x06;
+    }
+
+    let value = difference as Value;
+    let (_, carry_out, overflow) = subtract_binary(a, b, carry);
+
+    DecimalResult {
+        value,
+        carry: carry_out,
+        zero: value == 0,
+        negative: value & 0x80 != 0,
+        overflow,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::processor::decimal::{adc_cmos, adc_nmos, sbc_cmos, sbc_nmos, DecimalResult};
+    use crate::processor::Value;
+
+    // reference models written independently of the sequences above, digit by
+    // digit in the style of the VICE and MAME cores
+
+    fn reference_adc_nmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+        let mut sum = (a & 0x0f) as u16 + (b & 0x0f) as u16 + carry as u16;
+        if sum > 0x09 {
+            sum += 0x06;
+        }
+        sum = if sum <= 0x0f {
+            (sum & 0x0f) + (a & 0xf0) as u16 + (b & 0xf0) as u16
+        } else {
+            (sum & 0x0f) + (a & 0xf0) as u16 + (b & 0xf0) as u16 + 0x10
+        };
+
+        let zero = (a as u16 + b as u16 + carry as u16) & 0xff == 0;
+        let negative = sum & 0x80 != 0;
+        let overflow = (a as u16 ^ sum) & 0x80 != 0 && (a ^ b) & 0x80 == 0;
+
+        if sum & 0x1f0 > 0x90 {
+            sum += 0x60;
+        }
+
+        DecimalResult { value: sum as Value, carry: sum & 0xff0 > 0xf0, zero, negative, overflow }
+    }
+
+    fn reference_sbc_nmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+        let borrow = !carry as u16;
+        let binary = (a as u16).wrapping_sub(b as u16).wrapping_sub(borrow);
+
+        let mut low = ((a & 0x0f) as u16).wrapping_sub((b & 0x0f) as u16).wrapping_sub(borrow);
+        low = if low & 0x10 != 0 {
+            (low.wrapping_sub(6) & 0x0f) | ((a & 0xf0) as u16).wrapping_sub((b & 0xf0) as u16).wrapping_sub(0x10)
+        } else {
+            (low & 0x0f) | ((a & 0xf0) as u16).wrapping_sub((b & 0xf0) as u16)
+        };
+        if low & 0x100 != 0 {
+            low = low.wrapping_sub(0x60);
+        }
+
+        DecimalResult {
+            value: low as Value,
+            carry: binary < 0x100,
+            zero: binary & 0xff == 0,
+            negative: binary & 0x80 != 0,
+            overflow: (a as u16 ^ binary) & 0x80 != 0 && (a ^ b) & 0x80 != 0,
+        }
+    }
+
+    fn reference_adc_cmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+        let nmos = reference_adc_nmos(a, b, carry);
+        DecimalResult { zero: nmos.value == 0, negative: nmos.value & 0x80 != 0, ..nmos }
+    }
+
+    fn reference_sbc_cmos(a: Value, b: Value, carry: bool) -> DecimalResult {
+        let borrow = !carry as i16;
+        let low = (a & 0x0f) as i16 - (b & 0x0f) as i16 - borrow;
+        let high = (a >> 4) as i16 - (b >> 4) as i16 - (low < 0) as i16;
+
+        let mut value = (((high & 0x0f) << 4) | (low & 0x0f)) as Value;
+        if high < 0 {
+            value = value.wrapping_sub(0x60);
+        }
+        if low < 0 {
+            value = value.wrapping_sub(0x06);
+        }
+
+        let nmos = reference_sbc_nmos(a, b, carry);
+        DecimalResult { value, zero: value == 0, negative: value & 0x80 != 0, ..nmos }
+    }
+
+    // every accumulator, operand and carry in combination
+    fn reference_table(reference: fn(Value, Value, bool) -> DecimalResult) -> Vec<(Value, Value, bool, DecimalResult)> {
+        let mut table = Vec::with_capacity(256 * 256 * 2);
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                for carry in [false, true] {
+                    table.push((a, b, carry, reference(a, b, carry)));
+                }
+            }
+        }
+        table
+    }
+
+    fn assert_matches(
+        operation: fn(Value, Value, bool) -> DecimalResult,
+        reference: fn(Value, Value, bool) -> DecimalResult,
+    ) {
+        let table = reference_table(reference);
+        assert_eq!(table.len(), 131072);
+
+        for (a, b, carry, expected) in table {
+            assert_eq!(operation(a, b, carry), expected, "a={:#04x} b={:#04x} c={}", a, b, carry);
+        }
+    }
+
+    #[test]
+    fn test_adc_nmos() {
+        assert_matches(adc_nmos, reference_adc_nmos);
+    }
+
+    #[test]
+    fn test_adc_cmos() {
+        assert_matches(adc_cmos, reference_adc_cmos);
+    }
+
+    #[test]
+    fn test_sbc_nmos() {
+        assert_matches(sbc_nmos, reference_sbc_nmos);
+    }
+
+    #[test]
+    fn test_sbc_cmos() {
+        assert_matches(sbc_cmos, reference_sbc_cmos);
+    }
+
+    #[test]
+    fn test_valid_bcd() {
+        let result = adc_cmos(0x58, 0x46, true);
+        assert_eq!(result.value, 0x05);
+        assert!(result.carry);
+
+        let result = adc_cmos(0x99, 0x01, false);
+        assert_eq!(result.value, 0x00);
+        assert!(result.carry);
+        assert!(result.zero);
+
+        // the NMOS zero flag follows the binary sum
+        let result = adc_nmos(0x99, 0x01, false);
+        assert_eq!(result.value, 0x00);
+        assert!(!result.zero);
+
+        let result = sbc_cmos(0x46, 0x12, true);
+        assert_eq!(result.value, 0x34);
+        assert!(result.carry);
+
+        let result = sbc_cmos(0x12, 0x21, true);
+        assert_eq!(result.value, 0x91);
+        assert!(!result.carry);
+        assert!(result.negative);
+    }
+}
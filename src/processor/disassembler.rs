@@ -0,0 +1,154 @@
+use std::fmt::{Display, Formatter};
+use crate::memory::address::{Address, AddressMode};
+use crate::memory::Memory;
+use crate::processor::op_codes::OpCode;
+use crate::processor::variant::Variant;
+
+// one decoded instruction, formatted in the usual assembler syntax
+#[derive(Debug, Clone, Copy)]
+pub struct Disassembly {
+    pub address: Address,
+    pub op_code: &'static OpCode,
+    pub address_mode: AddressMode,
+}
+
+impl Disassembly {
+    pub fn bytes(&self) -> u8 {
+        self.op_code.execution_metrics.bytes
+    }
+
+    // the address a relative operand lands on, counted from the next instruction
+    fn branch_target(&self, offset: i8) -> Address {
+        let next_instruction = self.address + self.bytes();
+        Address(next_instruction.0.wrapping_add(offset as u16))
+    }
+}
+
+// decodes the instruction at the address the same way the processor does, including
+// the variant's undocumented op codes. None for a byte that decodes to nothing
+pub fn disassemble<V: Variant, M: Memory>(memory: &M, address: Address) -> Option<Disassembly> {
+    let op_code = memory.peek(&address);
+    let op_code = V::decode(op_code).or_else(|| V::decode_undocumented(op_code))?;
+
+    Some(Disassembly {
+        address,
+        op_code,
+        address_mode: AddressMode::peek_operand(memory, address, &op_code.address_mode),
+    })
+}
+
+impl Display for Disassembly {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.op_code.instruction)?;
+
+        match self.address_mode {
+            AddressMode::Implied => Ok(()),
+            AddressMode::Accumulator => write!(f, " A"),
+            AddressMode::Immediate(value) => write!(f, " #${:02X}", value),
+            AddressMode::ZeroPage(zp_address) => write!(f, " ${:02X}", zp_address.0),
+            AddressMode::ZeroPageX(zp_address) => write!(f, " ${:02X},X", zp_address.0),
+            AddressMode::ZeroPageY(zp_address) => write!(f, " ${:02X},Y", zp_address.0),
+            AddressMode::Absolute(address) => write!(f, " ${:04X}", address.0),
+            AddressMode::AbsoluteX(address) => write!(f, " ${:04X},X", address.0),
+            AddressMode::AbsoluteY(address) => write!(f, " ${:04X},Y", address.0),
+            AddressMode::Indirect(address) => write!(f, " (${:04X})", address.0),
+            AddressMode::PreIndexedIndirectX(zp_address) => write!(f, " (${:02X},X)", zp_address.0),
+            AddressMode::PostIndexedIndirectY(zp_address) => write!(f, " (${:02X}),Y", zp_address.0),
+            AddressMode::Relative(offset) => write!(f, " ${:04X}", self.branch_target(offset).0),
+            AddressMode::ZeroPageIndirect(zp_address) => write!(f, " (${:02X})", zp_address.0),
+            AddressMode::AbsoluteIndexedIndirectX(address) => write!(f, " (${:04X},X)", address.0),
+            AddressMode::ZeroPageRelative(zp_address, offset) => {
+                write!(f, " ${:02X},${:04X}", zp_address.0, self.branch_target(offset).0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::Address;
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::Cmos;
+    use crate::processor::disassembler::disassemble;
+    use crate::processor::nmos::Nmos;
+    use crate::processor::variant::Variant;
+
+    fn listing<V: Variant>(memory: &VecMemory, start: u16, end: u16) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        let mut address = Address(start);
+        while address.0 < end {
+            let disassembly = disassemble::<V, _>(memory, address).unwrap();
+            lines.push(disassembly.to_string());
+            address = address + disassembly.bytes();
+        }
+
+        lines
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let mut memory = VecMemory::default();
+        let program: &[u8] = &[
+            0x0A,             // ASL A
+            0x18,             // CLC
+            0x1A,             // INC A
+            0xA9, 0x05,       // LDA #$05
+            0xB6, 0x10,       // LDX $10,Y
+            0x6C, 0x34, 0x12, // JMP ($1234)
+            0xB1, 0x20,       // LDA ($20),Y
+            0x81, 0x20,       // STA ($20,X)
+            0xB2, 0x20,       // LDA ($20)
+            0x7C, 0x00, 0x30, // JMP ($3000,X)
+            0xD0, 0xeb,       // BNE back to the start
+            0x8F, 0x40, 0x10, // BBS0 $40,+16
+        ];
+        for (i, byte) in program.iter().enumerate() {
+            memory.write(&Address(0x0200 + i as u16), byte);
+        }
+
+        assert_eq!(listing::<Cmos>(&memory, 0x0200, 0x0200 + program.len() as u16), vec![
+            "ASL A",
+            "CLC",
+            "INC A",
+            "LDA #$05",
+            "LDX $10,Y",
+            "JMP ($1234)",
+            "LDA ($20),Y",
+            "STA ($20,X)",
+            "LDA ($20)",
+            "JMP ($3000,X)",
+            "BNE $0200",
+            "BBS0 $40,$0228",
+        ]);
+    }
+
+    #[test]
+    fn test_disassemble_variants() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x0000), &0xB2);
+        memory.write(&Address(0x0001), &0x20);
+
+        assert_eq!(disassemble::<Cmos, _>(&memory, Address(0x0000)).unwrap().to_string(), "LDA ($20)");
+        assert_eq!(disassemble::<Nmos, _>(&memory, Address(0x0000)).unwrap().to_string(), "JAM");
+    }
+
+    fn assert_disassembly_agrees_with_decode<V: Variant>() {
+        let mut memory = VecMemory::default();
+
+        for op_code in V::op_codes().iter().chain(V::undocumented_op_codes()) {
+            memory.write(&Address(0x0000), &op_code.execution_metrics.op_code);
+
+            let disassembly = disassemble::<V, _>(&memory, Address(0x0000)).unwrap();
+            assert_eq!(disassembly.op_code.instruction, op_code.instruction);
+            assert_eq!(disassembly.address_mode.kind(), op_code.address_mode);
+        }
+    }
+
+    #[test]
+    fn test_disassembly_agrees_with_decode() {
+        assert_disassembly_agrees_with_decode::<Nmos>();
+        assert_disassembly_agrees_with_decode::<Cmos>();
+    }
+}
@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::memory::address::AddressModeKind;
+use crate::processor::Instruction;
+
+// why the processor couldn't carry on, returned instead of aborting on bad guest code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionError {
+    // nothing decodes from the op code, on this variant with the op codes enabled
+    InvalidOpCode { op_code: u8, address: u16 },
+
+    // the instruction has no form using the address mode
+    IllegalAddressMode { instruction: Instruction, address_mode: AddressModeKind },
+
+    // the address mode doesn't give the instruction what it works on, like an address to store to
+    AddressModeMismatch(AddressModeKind),
+
+    // JAM locked the processor up at the address, only a reset recovers
+    Jammed { address: u16 },
+
+    // STP stopped the clock, only a reset recovers
+    Stopped,
+
+    // the instruction wrote the value to read only memory, it still ran to the end
+    WriteToRom { address: u16, value: u8 },
+}
+
+impl Display for ExecutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionError::InvalidOpCode { op_code, address } => {
+                write!(f, "unsupported op code {:#04x} at {:#06x}", op_code, address)
+            }
+            ExecutionError::IllegalAddressMode { instruction, address_mode } => {
+                write!(f, "{:?} does not have a definition for address mode {:?}", instruction, address_mode)
+            }
+            ExecutionError::AddressModeMismatch(address_mode) => {
+                write!(f, "address mode {:?} doesn't fit the instruction", address_mode)
+            }
+            ExecutionError::Jammed { address } => write!(f, "processor jammed at {:#06x}", address),
+            ExecutionError::Stopped => write!(f, "processor stopped"),
+            ExecutionError::WriteToRom { address, value } => {
+                write!(f, "wrote {:#04x} to read only memory at {:#06x}", value, address)
+            }
+        }
+    }
+}
+
+impl Error for ExecutionError {}
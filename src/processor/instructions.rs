@@ -1,12 +1,29 @@
-use crate::memory::address::AddressMode;
-use crate::processor::ExecutionMetrics;
-
-#[allow(nonstandard_style, unused)]
-#[derive(Debug, Clone, Copy)]
+#[allow(nonstandard_style, unused, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
     ADC, // add with carry (immediate)
+    ALR, // and then shift right (NMOS undocumented)
+    ANC, // and, copying negative into carry (NMOS undocumented)
     AND, // and (with accumulator)
+    ANE, // and X with accumulator and a magic constant (NMOS unstable)
+    ARR, // and then rotate right (NMOS undocumented)
     ASL, // arithmetic shift left
+    BBR0, // branch on bit 0 reset (65C02)
+    BBR1, // branch on bit 1 reset (65C02)
+    BBR2, // branch on bit 2 reset (65C02)
+    BBR3, // branch on bit 3 reset (65C02)
+    BBR4, // branch on bit 4 reset (65C02)
+    BBR5, // branch on bit 5 reset (65C02)
+    BBR6, // branch on bit 6 reset (65C02)
+    BBR7, // branch on bit 7 reset (65C02)
+    BBS0, // branch on bit 0 set (65C02)
+    BBS1, // branch on bit 1 set (65C02)
+    BBS2, // branch on bit 2 set (65C02)
+    BBS3, // branch on bit 3 set (65C02)
+    BBS4, // branch on bit 4 set (65C02)
+    BBS5, // branch on bit 5 set (65C02)
+    BBS6, // branch on bit 6 set (65C02)
+    BBS7, // branch on bit 7 set (65C02)
     BCC, // branch on carry clear
     BCS, // branch on carry set
     BEQ, // branch on equal (zero set)
@@ -14,6 +31,7 @@ pub enum Instruction {
     BMI, // branch on minus (negative set)
     BNE, // branch on not equal (zero clear)
     BPL, // branch on plus (negative clear)
+    BRA, // branch always (65C02)
     BRK, // break / interrupt
     BVC, // branch on overflow clear
     BVS, // branch on overflow set
@@ -24,6 +42,7 @@ pub enum Instruction {
     CMP, // compare (with accumulator)
     CPX, // compare with X
     CPY, // compare with Y
+    DCP, // decrement then compare (NMOS undocumented)
     DEC, // decrement
     DEX, // decrement X
     DEY, // decrement Y
@@ -31,123 +50,73 @@ pub enum Instruction {
     INC, // increment
     INX, // increment X
     INY, // increment Y
+    ISC, // increment then subtract with carry (NMOS undocumented)
+    JAM, // lock up until reset (NMOS undocumented)
     JMP, // jump
     JSR, // jump subroutine
+    LAS, // and with stack pointer into accumulator, X and stack pointer (NMOS undocumented)
+    LAX, // load accumulator and X (NMOS undocumented)
     LDA, // load accumulator
     LDX, // load X
     LDY, // load Y
     LSR, // logical shift right
+    LXA, // load accumulator and X through a magic constant (NMOS unstable)
     NOP, // no operation
     ORA, // or with accumulator
     PHA, // push accumulator
     PHP, // push processor status (SR)
+    PHX, // push X (65C02)
+    PHY, // push Y (65C02)
     PLA, // pull accumulator
     PLP, // pull processor status (SR)
+    PLX, // pull X (65C02)
+    PLY, // pull Y (65C02)
+    RLA, // rotate left then and with accumulator (NMOS undocumented)
+    RMB0, // reset memory bit 0 (65C02)
+    RMB1, // reset memory bit 1 (65C02)
+    RMB2, // reset memory bit 2 (65C02)
+    RMB3, // reset memory bit 3 (65C02)
+    RMB4, // reset memory bit 4 (65C02)
+    RMB5, // reset memory bit 5 (65C02)
+    RMB6, // reset memory bit 6 (65C02)
+    RMB7, // reset memory bit 7 (65C02)
     ROL, // rotate left
     ROR, // rotate right
+    RRA, // rotate right then add with carry (NMOS undocumented)
     RTI, // return from interrupt
     RTS, // return from subroutine
+    SAX, // store accumulator and X (NMOS undocumented)
     SBC, // subtract with carry
+    SBX, // and X with accumulator then subtract (NMOS undocumented)
     SEC, // set carry
     SED, // set decimal
     SEI, // set interrupt disable
+    SHA, // store accumulator and X and high byte (NMOS unstable)
+    SHX, // store X and high byte (NMOS unstable)
+    SHY, // store Y and high byte (NMOS unstable)
+    SLO, // shift left then or with accumulator (NMOS undocumented)
+    SMB0, // set memory bit 0 (65C02)
+    SMB1, // set memory bit 1 (65C02)
+    SMB2, // set memory bit 2 (65C02)
+    SMB3, // set memory bit 3 (65C02)
+    SMB4, // set memory bit 4 (65C02)
+    SMB5, // set memory bit 5 (65C02)
+    SMB6, // set memory bit 6 (65C02)
+    SMB7, // set memory bit 7 (65C02)
+    SRE, // shift right then exclusive or with accumulator (NMOS undocumented)
     STA, // store accumulator
+    STP, // stop the clock until reset (65C02)
     STX, // store X
     STY, // store Y
+    STZ, // store zero (65C02)
+    TAS, // transfer accumulator and X to stack pointer, store with high byte (NMOS unstable)
     TAX, // transfer accumulator to X
     TAY, // transfer accumulator to Y
+    TRB, // test and reset bits (65C02)
+    TSB, // test and set bits (65C02)
     TSX, // transfer stack pointer to X
     TXA, // transfer X to accumulator
     TXS, // transfer X to stack pointer
     TYA, // transfer Y to accumulator
-}
-
-impl Instruction {
-    pub(crate) fn execution_metrics(&self, address_mode: &AddressMode) -> Option<ExecutionMetrics> {
-        match self {
-            Instruction::ADC => match address_mode {
-                AddressMode::Immediate(_) => Some(ExecutionMetrics::new(0x69, 2, 2)),
-                AddressMode::ZeroPage(_) => Some(ExecutionMetrics::new(0x65, 2, 3)),
-                AddressMode::ZeroPageX(_) => Some(ExecutionMetrics::new(0x75, 2, 4)),
-                AddressMode::Absolute(_) => Some(ExecutionMetrics::new(0x6d, 3, 4)),
-                AddressMode::AbsoluteX(_) => Some(ExecutionMetrics::new(0x7d, 3, 4)),
-                AddressMode::AbsoluteY(_) => Some(ExecutionMetrics::new(0x79, 3, 4)),
-                AddressMode::PreIndexedIndirectX(_) => Some(ExecutionMetrics::new(0x61, 2, 6)),
-                AddressMode::PostIndexedIndirectY(_) => Some(ExecutionMetrics::new(0x71, 2, 5)),
-                _ => None,
-            }
-            Instruction::AND => match address_mode {
-                AddressMode::Immediate(_) => Some(ExecutionMetrics::new(0x29, 2, 2)),
-                AddressMode::ZeroPage(_) => Some(ExecutionMetrics::new(0x25, 2, 3)),
-                AddressMode::ZeroPageX(_) => Some(ExecutionMetrics::new(0x35, 2, 4)),
-                AddressMode::Absolute(_) => Some(ExecutionMetrics::new(0x2D, 3, 4)),
-                AddressMode::AbsoluteX(_) => Some(ExecutionMetrics::new(0x3D, 3, 4)),
-                AddressMode::AbsoluteY(_) => Some(ExecutionMetrics::new(0x39, 3, 4)),
-                AddressMode::PreIndexedIndirectX(_) => Some(ExecutionMetrics::new(0x21, 2, 6)),
-                AddressMode::PostIndexedIndirectY(_) => Some(ExecutionMetrics::new(0x31, 2, 5)),
-                _ => None,
-            }
-            Instruction::ASL => match address_mode {
-                AddressMode::Implied => Some(ExecutionMetrics::new(0x0A, 1, 2)),
-                AddressMode::ZeroPage(_) => Some(ExecutionMetrics::new(0x06, 2, 5)),
-                AddressMode::ZeroPageX(_) => Some(ExecutionMetrics::new(0x16, 2, 6)),
-                AddressMode::Absolute(_) => Some(ExecutionMetrics::new(0x0E, 3, 6)),
-                AddressMode::AbsoluteX(_) => Some(ExecutionMetrics::new(0x3D, 3, 4)),
-                _ => None,
-            },
-            Instruction::BCC => unimplemented!(),
-            Instruction::BCS => unimplemented!(),
-            Instruction::BEQ => unimplemented!(),
-            Instruction::BIT => unimplemented!(),
-            Instruction::BMI => unimplemented!(),
-            Instruction::BNE => unimplemented!(),
-            Instruction::BPL => unimplemented!(),
-            Instruction::BRK => unimplemented!(),
-            Instruction::BVC => unimplemented!(),
-            Instruction::BVS => unimplemented!(),
-            Instruction::CLC => unimplemented!(),
-            Instruction::CLD => unimplemented!(),
-            Instruction::CLI => unimplemented!(),
-            Instruction::CLV => unimplemented!(),
-            Instruction::CMP => unimplemented!(),
-            Instruction::CPX => unimplemented!(),
-            Instruction::CPY => unimplemented!(),
-            Instruction::DEC => unimplemented!(),
-            Instruction::DEX => unimplemented!(),
-            Instruction::DEY => unimplemented!(),
-            Instruction::EOR => unimplemented!(),
-            Instruction::INC => unimplemented!(),
-            Instruction::INX => unimplemented!(),
-            Instruction::INY => unimplemented!(),
-            Instruction::JMP => unimplemented!(),
-            Instruction::JSR => unimplemented!(),
-            Instruction::LDA => unimplemented!(),
-            Instruction::LDX => unimplemented!(),
-            Instruction::LDY => unimplemented!(),
-            Instruction::LSR => unimplemented!(),
-            Instruction::NOP => unimplemented!(),
-            Instruction::ORA => unimplemented!(),
-            Instruction::PHA => unimplemented!(),
-            Instruction::PHP => unimplemented!(),
-            Instruction::PLA => unimplemented!(),
-            Instruction::PLP => unimplemented!(),
-            Instruction::ROL => unimplemented!(),
-            Instruction::ROR => unimplemented!(),
-            Instruction::RTI => unimplemented!(),
-            Instruction::RTS => unimplemented!(),
-            Instruction::SBC => unimplemented!(),
-            Instruction::SEC => unimplemented!(),
-            Instruction::SED => unimplemented!(),
-            Instruction::SEI => unimplemented!(),
-            Instruction::STA => unimplemented!(),
-            Instruction::STX => unimplemented!(),
-            Instruction::STY => unimplemented!(),
-            Instruction::TAX => unimplemented!(),
-            Instruction::TAY => unimplemented!(),
-            Instruction::TSX => unimplemented!(),
-            Instruction::TXA => unimplemented!(),
-            Instruction::TXS => unimplemented!(),
-            Instruction::TYA => unimplemented!(),
-        }
-    }
+    WAI, // wait for interrupt (65C02)
 }
@@ -1,13 +1,27 @@
 pub mod cmos;
+pub mod core;
+mod decimal;
+pub mod disassembler;
+pub mod error;
 mod instructions;
-mod status;
+pub mod nmos;
+pub mod op_codes;
+pub mod registers;
+pub mod status;
+pub mod variant;
 
+pub use decimal::DecimalResult;
+pub use error::ExecutionError;
 pub use instructions::Instruction;
+pub use registers::Registers;
+pub use self::core::{Processor, RunState};
+pub use self::core::undocumented::UnstablePolicy;
 
 pub type Value = u8;
 type Register16 = u16;
 type Register8 = u8;
 
+#[derive(Debug, Clone, Copy)]
 pub struct ExecutionMetrics {
     pub op_code: u8,
     pub bytes: u8,
@@ -15,7 +29,7 @@ pub struct ExecutionMetrics {
 }
 
 impl ExecutionMetrics {
-    pub fn new(op_code: u8, bytes: u8, cycles: u8) -> Self {
+    pub const fn new(op_code: u8, bytes: u8, cycles: u8) -> Self {
         Self {
             op_code,
             bytes,
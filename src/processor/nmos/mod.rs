@@ -0,0 +1,148 @@
+use crate::processor::core::Processor;
+use crate::processor::decimal::DecimalResult;
+use crate::processor::op_codes::{OpCode, NMOS_DECODE_TABLE, NMOS_OP_CODES, NMOS_UNDOCUMENTED_DECODE_TABLE, NMOS_UNDOCUMENTED_OP_CODES};
+use crate::processor::variant::Variant;
+use crate::processor::{decimal, Value};
+
+// the original NMOS 6502, as found in the C64, NES and Apple II+
+pub struct Nmos;
+
+impl Variant for Nmos {
+    const DECIMAL_CYCLES: u8 = 0;
+    const INTERRUPT_CLEARS_DECIMAL: bool = false;
+    const INDIRECT_PAGE_WRAP: bool = true;
+    const READ_MODIFY_WRITE_DOUBLE_WRITE: bool = true;
+    const PAGE_CROSS_READS_INVALID_ADDRESS: bool = true;
+    const SHIFT_INDEXED_PAGE_PENALTY: bool = false;
+
+    fn op_codes() -> &'static [OpCode] {
+        &NMOS_OP_CODES
+    }
+
+    fn decode(op_code: u8) -> Option<&'static OpCode> {
+        NMOS_DECODE_TABLE[op_code as usize].as_ref()
+    }
+
+    fn undocumented_op_codes() -> &'static [OpCode] {
+        &NMOS_UNDOCUMENTED_OP_CODES
+    }
+
+    fn decode_undocumented(op_code: u8) -> Option<&'static OpCode> {
+        NMOS_UNDOCUMENTED_DECODE_TABLE[op_code as usize].as_ref()
+    }
+
+    fn adc_decimal(a: Value, b: Value, carry: bool) -> DecimalResult {
+        decimal::adc_nmos(a, b, carry)
+    }
+
+    fn sbc_decimal(a: Value, b: Value, carry: bool) -> DecimalResult {
+        decimal::sbc_nmos(a, b, carry)
+    }
+}
+
+pub type NmosProcessor<M> = Processor<M, Nmos>;
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::{Address, AddressMode, ZeroPageAddress};
+    use crate::memory::Memory;
+    use crate::memory::vec_memory::VecMemory;
+    use crate::processor::cmos::CmosProcessor;
+    use crate::processor::nmos::NmosProcessor;
+    use crate::processor::status::{FLAG_CARRY, FLAG_DECIMAL, FLAG_INTERRUPT_DISABLE, FLAG_ZERO};
+    use crate::processor::{Instruction, Value};
+
+    // keeps every write so the bus traffic can be checked
+    #[derive(Default)]
+    struct WriteLog {
+        memory: VecMemory,
+        writes: Vec<(u16, Value)>,
+    }
+
+    impl Memory for WriteLog {
+        fn peek(&self, address: &Address) -> Value {
+            self.memory.peek(address)
+        }
+
+        fn write(&mut self, address: &Address, value: &Value) {
+            self.writes.push((address.0, *value));
+            self.memory.write(address, value);
+        }
+    }
+
+    #[test]
+    fn test_decimal() {
+        let mut memory = VecMemory::default();
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.execute(&Instruction::SED, &AddressMode::Implied).unwrap();
+
+        // no extra cycle, and zero follows the binary sum
+        processor.accumulator = 0x99;
+        let cycles = processor.execute(&Instruction::ADC, &AddressMode::Immediate(0x01)).unwrap();
+        assert_eq!(cycles, 2);
+        assert_eq!(processor.accumulator, 0x00);
+        assert!(processor.status.get_bit(FLAG_CARRY));
+        assert!(!processor.status.get_bit(FLAG_ZERO));
+
+        processor.accumulator = 0x46;
+        let cycles = processor.execute(&Instruction::SBC, &AddressMode::Immediate(0x12)).unwrap();
+        assert_eq!(cycles, 2);
+        assert_eq!(processor.accumulator, 0x34);
+    }
+
+    #[test]
+    fn test_jmp_indirect_page_wrap() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0x10ff), &0x34);
+        memory.write(&Address(0x1000), &0x12);
+        memory.write(&Address(0x1100), &0x56);
+
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        let cycles = processor.execute(&Instruction::JMP, &AddressMode::Indirect(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 5);
+        assert_eq!(processor.program_counter, 0x1234);
+
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        let cycles = processor.execute(&Instruction::JMP, &AddressMode::Indirect(Address(0x10ff))).unwrap();
+        assert_eq!(cycles, 6);
+        assert_eq!(processor.program_counter, 0x5634);
+    }
+
+    #[test]
+    fn test_read_modify_write_double_write() {
+        let mut memory = WriteLog::default();
+        memory.write(&Address(0x0040), &0x01);
+        memory.writes.clear();
+
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.execute(&Instruction::INC, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        processor.execute(&Instruction::ASL, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(memory.writes, vec![(0x0040, 0x01), (0x0040, 0x02), (0x0040, 0x02), (0x0040, 0x04)]);
+
+        memory.writes.clear();
+        let mut processor = CmosProcessor::with_memory(&mut memory);
+        processor.execute(&Instruction::INC, &AddressMode::ZeroPage(ZeroPageAddress(0x40))).unwrap();
+        assert_eq!(memory.writes, vec![(0x0040, 0x05)]);
+    }
+
+    #[test]
+    fn test_interrupts_keep_decimal() {
+        let mut memory = VecMemory::default();
+        memory.write(&Address(0xffff), &0x30);
+
+        let mut processor = NmosProcessor::with_memory(&mut memory);
+        processor.status.enable_bit(FLAG_DECIMAL);
+
+        processor.execute(&Instruction::BRK, &AddressMode::Implied).unwrap();
+        assert_eq!(processor.program_counter, 0x3000);
+        assert!(processor.status.get_bit(FLAG_INTERRUPT_DISABLE));
+        assert!(processor.status.get_bit(FLAG_DECIMAL));
+
+        processor.set_nmi(true);
+        processor.step().unwrap();
+        assert!(processor.status.get_bit(FLAG_DECIMAL));
+
+        processor.reset();
+        assert!(processor.status.get_bit(FLAG_DECIMAL));
+    }
+}
@@ -0,0 +1,633 @@
+use crate::memory::address::AddressModeKind;
+use crate::processor::{ExecutionMetrics, Instruction};
+
+#[derive(Debug, Clone, Copy)]
+pub struct OpCode {
+    pub instruction: Instruction,
+    pub address_mode: AddressModeKind,
+    pub execution_metrics: ExecutionMetrics,
+}
+
+const fn op_code(op_code: u8, instruction: Instruction, address_mode: AddressModeKind, bytes: u8, cycles: u8) -> OpCode {
+    OpCode {
+        instruction,
+        address_mode,
+        execution_metrics: ExecutionMetrics::new(op_code, bytes, cycles),
+    }
+}
+
+// the op codes shared by every variant, with the timings of the original NMOS part.
+// the variant tables below are built from these and are the single source of truth
+// for both directions, decoding and looking up metrics
+pub(crate) const DOCUMENTED_OP_CODES: [OpCode; 151] = [
+    op_code(0x69, Instruction::ADC, AddressModeKind::Immediate, 2, 2),
+    op_code(0x65, Instruction::ADC, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x75, Instruction::ADC, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x6D, Instruction::ADC, AddressModeKind::Absolute, 3, 4),
+    op_code(0x7D, Instruction::ADC, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x79, Instruction::ADC, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0x61, Instruction::ADC, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0x71, Instruction::ADC, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0x29, Instruction::AND, AddressModeKind::Immediate, 2, 2),
+    op_code(0x25, Instruction::AND, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x35, Instruction::AND, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x2D, Instruction::AND, AddressModeKind::Absolute, 3, 4),
+    op_code(0x3D, Instruction::AND, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x39, Instruction::AND, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0x21, Instruction::AND, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0x31, Instruction::AND, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0x0A, Instruction::ASL, AddressModeKind::Accumulator, 1, 2),
+    op_code(0x06, Instruction::ASL, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x16, Instruction::ASL, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x0E, Instruction::ASL, AddressModeKind::Absolute, 3, 6),
+    op_code(0x1E, Instruction::ASL, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x90, Instruction::BCC, AddressModeKind::Relative, 2, 2),
+    op_code(0xB0, Instruction::BCS, AddressModeKind::Relative, 2, 2),
+    op_code(0xF0, Instruction::BEQ, AddressModeKind::Relative, 2, 2),
+    op_code(0x24, Instruction::BIT, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x2C, Instruction::BIT, AddressModeKind::Absolute, 3, 4),
+    op_code(0x30, Instruction::BMI, AddressModeKind::Relative, 2, 2),
+    op_code(0xD0, Instruction::BNE, AddressModeKind::Relative, 2, 2),
+    op_code(0x10, Instruction::BPL, AddressModeKind::Relative, 2, 2),
+    op_code(0x00, Instruction::BRK, AddressModeKind::Implied, 1, 7),
+    op_code(0x50, Instruction::BVC, AddressModeKind::Relative, 2, 2),
+    op_code(0x70, Instruction::BVS, AddressModeKind::Relative, 2, 2),
+    op_code(0x18, Instruction::CLC, AddressModeKind::Implied, 1, 2),
+    op_code(0xD8, Instruction::CLD, AddressModeKind::Implied, 1, 2),
+    op_code(0x58, Instruction::CLI, AddressModeKind::Implied, 1, 2),
+    op_code(0xB8, Instruction::CLV, AddressModeKind::Implied, 1, 2),
+    op_code(0xC9, Instruction::CMP, AddressModeKind::Immediate, 2, 2),
+    op_code(0xC5, Instruction::CMP, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xD5, Instruction::CMP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xCD, Instruction::CMP, AddressModeKind::Absolute, 3, 4),
+    op_code(0xDD, Instruction::CMP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0xD9, Instruction::CMP, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0xC1, Instruction::CMP, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0xD1, Instruction::CMP, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0xE0, Instruction::CPX, AddressModeKind::Immediate, 2, 2),
+    op_code(0xE4, Instruction::CPX, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xEC, Instruction::CPX, AddressModeKind::Absolute, 3, 4),
+    op_code(0xC0, Instruction::CPY, AddressModeKind::Immediate, 2, 2),
+    op_code(0xC4, Instruction::CPY, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xCC, Instruction::CPY, AddressModeKind::Absolute, 3, 4),
+    op_code(0xC6, Instruction::DEC, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xD6, Instruction::DEC, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0xCE, Instruction::DEC, AddressModeKind::Absolute, 3, 6),
+    op_code(0xDE, Instruction::DEC, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0xCA, Instruction::DEX, AddressModeKind::Implied, 1, 2),
+    op_code(0x88, Instruction::DEY, AddressModeKind::Implied, 1, 2),
+    op_code(0x49, Instruction::EOR, AddressModeKind::Immediate, 2, 2),
+    op_code(0x45, Instruction::EOR, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x55, Instruction::EOR, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x4D, Instruction::EOR, AddressModeKind::Absolute, 3, 4),
+    op_code(0x5D, Instruction::EOR, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x59, Instruction::EOR, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0x41, Instruction::EOR, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0x51, Instruction::EOR, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0xE6, Instruction::INC, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xF6, Instruction::INC, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0xEE, Instruction::INC, AddressModeKind::Absolute, 3, 6),
+    op_code(0xFE, Instruction::INC, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0xE8, Instruction::INX, AddressModeKind::Implied, 1, 2),
+    op_code(0xC8, Instruction::INY, AddressModeKind::Implied, 1, 2),
+    op_code(0x4C, Instruction::JMP, AddressModeKind::Absolute, 3, 3),
+    op_code(0x6C, Instruction::JMP, AddressModeKind::Indirect, 3, 5),
+    op_code(0x20, Instruction::JSR, AddressModeKind::Absolute, 3, 6),
+    op_code(0xA9, Instruction::LDA, AddressModeKind::Immediate, 2, 2),
+    op_code(0xA5, Instruction::LDA, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xB5, Instruction::LDA, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xAD, Instruction::LDA, AddressModeKind::Absolute, 3, 4),
+    op_code(0xBD, Instruction::LDA, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0xB9, Instruction::LDA, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0xA1, Instruction::LDA, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0xB1, Instruction::LDA, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0xA2, Instruction::LDX, AddressModeKind::Immediate, 2, 2),
+    op_code(0xA6, Instruction::LDX, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xB6, Instruction::LDX, AddressModeKind::ZeroPageY, 2, 4),
+    op_code(0xAE, Instruction::LDX, AddressModeKind::Absolute, 3, 4),
+    op_code(0xBE, Instruction::LDX, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0xA0, Instruction::LDY, AddressModeKind::Immediate, 2, 2),
+    op_code(0xA4, Instruction::LDY, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xB4, Instruction::LDY, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xAC, Instruction::LDY, AddressModeKind::Absolute, 3, 4),
+    op_code(0xBC, Instruction::LDY, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x4A, Instruction::LSR, AddressModeKind::Accumulator, 1, 2),
+    op_code(0x46, Instruction::LSR, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x56, Instruction::LSR, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x4E, Instruction::LSR, AddressModeKind::Absolute, 3, 6),
+    op_code(0x5E, Instruction::LSR, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0xEA, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0x09, Instruction::ORA, AddressModeKind::Immediate, 2, 2),
+    op_code(0x05, Instruction::ORA, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x15, Instruction::ORA, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x0D, Instruction::ORA, AddressModeKind::Absolute, 3, 4),
+    op_code(0x1D, Instruction::ORA, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x19, Instruction::ORA, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0x01, Instruction::ORA, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0x11, Instruction::ORA, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0x48, Instruction::PHA, AddressModeKind::Implied, 1, 3),
+    op_code(0x08, Instruction::PHP, AddressModeKind::Implied, 1, 3),
+    op_code(0x68, Instruction::PLA, AddressModeKind::Implied, 1, 4),
+    op_code(0x28, Instruction::PLP, AddressModeKind::Implied, 1, 4),
+    op_code(0x2A, Instruction::ROL, AddressModeKind::Accumulator, 1, 2),
+    op_code(0x26, Instruction::ROL, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x36, Instruction::ROL, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x2E, Instruction::ROL, AddressModeKind::Absolute, 3, 6),
+    op_code(0x3E, Instruction::ROL, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x6A, Instruction::ROR, AddressModeKind::Accumulator, 1, 2),
+    op_code(0x66, Instruction::ROR, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x76, Instruction::ROR, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x6E, Instruction::ROR, AddressModeKind::Absolute, 3, 6),
+    op_code(0x7E, Instruction::ROR, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x40, Instruction::RTI, AddressModeKind::Implied, 1, 6),
+    op_code(0x60, Instruction::RTS, AddressModeKind::Implied, 1, 6),
+    op_code(0xE9, Instruction::SBC, AddressModeKind::Immediate, 2, 2),
+    op_code(0xE5, Instruction::SBC, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xF5, Instruction::SBC, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xED, Instruction::SBC, AddressModeKind::Absolute, 3, 4),
+    op_code(0xFD, Instruction::SBC, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0xF9, Instruction::SBC, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0xE1, Instruction::SBC, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0xF1, Instruction::SBC, AddressModeKind::PostIndexedIndirectY, 2, 5),
+    op_code(0x38, Instruction::SEC, AddressModeKind::Implied, 1, 2),
+    op_code(0xF8, Instruction::SED, AddressModeKind::Implied, 1, 2),
+    op_code(0x78, Instruction::SEI, AddressModeKind::Implied, 1, 2),
+    op_code(0x85, Instruction::STA, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x95, Instruction::STA, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x8D, Instruction::STA, AddressModeKind::Absolute, 3, 4),
+    op_code(0x9D, Instruction::STA, AddressModeKind::AbsoluteX, 3, 5),
+    op_code(0x99, Instruction::STA, AddressModeKind::AbsoluteY, 3, 5),
+    op_code(0x81, Instruction::STA, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0x91, Instruction::STA, AddressModeKind::PostIndexedIndirectY, 2, 6),
+    op_code(0x86, Instruction::STX, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x96, Instruction::STX, AddressModeKind::ZeroPageY, 2, 4),
+    op_code(0x8E, Instruction::STX, AddressModeKind::Absolute, 3, 4),
+    op_code(0x84, Instruction::STY, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x94, Instruction::STY, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x8C, Instruction::STY, AddressModeKind::Absolute, 3, 4),
+    op_code(0xAA, Instruction::TAX, AddressModeKind::Implied, 1, 2),
+    op_code(0xA8, Instruction::TAY, AddressModeKind::Implied, 1, 2),
+    op_code(0xBA, Instruction::TSX, AddressModeKind::Implied, 1, 2),
+    op_code(0x8A, Instruction::TXA, AddressModeKind::Implied, 1, 2),
+    op_code(0x9A, Instruction::TXS, AddressModeKind::Implied, 1, 2),
+    op_code(0x98, Instruction::TYA, AddressModeKind::Implied, 1, 2),
+];
+
+// where the 65C02 differs from the documented table, and its additions
+const CMOS_CHANGES: [OpCode; 66] = [
+    // a pointer at $xxFF no longer wraps within the page, at the cost of a cycle
+    op_code(0x6C, Instruction::JMP, AddressModeKind::Indirect, 3, 6),
+    op_code(0x7C, Instruction::JMP, AddressModeKind::AbsoluteIndexedIndirectX, 3, 6),
+
+    // shifts and rotates in abs,X only take the fix up cycle when indexing crosses a page,
+    // INC and DEC still always take it
+    op_code(0x1E, Instruction::ASL, AddressModeKind::AbsoluteX, 3, 6),
+    op_code(0x5E, Instruction::LSR, AddressModeKind::AbsoluteX, 3, 6),
+    op_code(0x3E, Instruction::ROL, AddressModeKind::AbsoluteX, 3, 6),
+    op_code(0x7E, Instruction::ROR, AddressModeKind::AbsoluteX, 3, 6),
+
+    op_code(0x12, Instruction::ORA, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0x32, Instruction::AND, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0x52, Instruction::EOR, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0x72, Instruction::ADC, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0x92, Instruction::STA, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0xB2, Instruction::LDA, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0xD2, Instruction::CMP, AddressModeKind::ZeroPageIndirect, 2, 5),
+    op_code(0xF2, Instruction::SBC, AddressModeKind::ZeroPageIndirect, 2, 5),
+
+    op_code(0x89, Instruction::BIT, AddressModeKind::Immediate, 2, 2),
+    op_code(0x34, Instruction::BIT, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x3C, Instruction::BIT, AddressModeKind::AbsoluteX, 3, 4),
+
+    op_code(0x1A, Instruction::INC, AddressModeKind::Accumulator, 1, 2),
+    op_code(0x3A, Instruction::DEC, AddressModeKind::Accumulator, 1, 2),
+
+    // always taken, the branch penalty is added like the other branches
+    op_code(0x80, Instruction::BRA, AddressModeKind::Relative, 2, 2),
+
+    op_code(0xDA, Instruction::PHX, AddressModeKind::Implied, 1, 3),
+    op_code(0x5A, Instruction::PHY, AddressModeKind::Implied, 1, 3),
+    op_code(0xFA, Instruction::PLX, AddressModeKind::Implied, 1, 4),
+    op_code(0x7A, Instruction::PLY, AddressModeKind::Implied, 1, 4),
+
+    op_code(0x64, Instruction::STZ, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x74, Instruction::STZ, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x9C, Instruction::STZ, AddressModeKind::Absolute, 3, 4),
+    op_code(0x9E, Instruction::STZ, AddressModeKind::AbsoluteX, 3, 5),
+
+    op_code(0x14, Instruction::TRB, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x1C, Instruction::TRB, AddressModeKind::Absolute, 3, 6),
+    op_code(0x04, Instruction::TSB, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x0C, Instruction::TSB, AddressModeKind::Absolute, 3, 6),
+
+    // Rockwell / WDC bit instructions
+    op_code(0x07, Instruction::RMB0, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x17, Instruction::RMB1, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x27, Instruction::RMB2, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x37, Instruction::RMB3, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x47, Instruction::RMB4, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x57, Instruction::RMB5, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x67, Instruction::RMB6, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x77, Instruction::RMB7, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x87, Instruction::SMB0, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x97, Instruction::SMB1, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xA7, Instruction::SMB2, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xB7, Instruction::SMB3, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xC7, Instruction::SMB4, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xD7, Instruction::SMB5, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xE7, Instruction::SMB6, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xF7, Instruction::SMB7, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x0F, Instruction::BBR0, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x1F, Instruction::BBR1, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x2F, Instruction::BBR2, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x3F, Instruction::BBR3, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x4F, Instruction::BBR4, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x5F, Instruction::BBR5, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x6F, Instruction::BBR6, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x7F, Instruction::BBR7, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x8F, Instruction::BBS0, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0x9F, Instruction::BBS1, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xAF, Instruction::BBS2, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xBF, Instruction::BBS3, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xCF, Instruction::BBS4, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xDF, Instruction::BBS5, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xEF, Instruction::BBS6, AddressModeKind::ZeroPageRelative, 3, 5),
+    op_code(0xFF, Instruction::BBS7, AddressModeKind::ZeroPageRelative, 3, 5),
+
+    // WDC low power
+    op_code(0xCB, Instruction::WAI, AddressModeKind::Implied, 1, 3),
+    op_code(0xDB, Instruction::STP, AddressModeKind::Implied, 1, 3),
+];
+
+
+// every op code the 65C02 leaves unassigned is a NOP of a fixed width and timing,
+// so any byte can be executed
+const CMOS_UNDEFINED_NOPS: [OpCode; 44] = [
+    op_code(0x03, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x0B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x13, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x1B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x23, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x2B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x33, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x3B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x43, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x4B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x53, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x5B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x63, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x6B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x73, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x7B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x83, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x8B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x93, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0x9B, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xA3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xAB, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xB3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xBB, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xC3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xD3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xE3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xEB, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xF3, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+    op_code(0xFB, Instruction::NOP, AddressModeKind::Implied, 1, 1),
+
+    op_code(0x02, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x22, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x42, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x62, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x82, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0xC2, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0xE2, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+
+    op_code(0x44, Instruction::NOP, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x54, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xD4, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xF4, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+
+    // reads its operand as an address but takes a good while over it
+    op_code(0x5C, Instruction::NOP, AddressModeKind::Absolute, 3, 8),
+    op_code(0xDC, Instruction::NOP, AddressModeKind::Absolute, 3, 4),
+    op_code(0xFC, Instruction::NOP, AddressModeKind::Absolute, 3, 4),
+];
+
+// the NMOS op codes left out of the documented set, only decoded once they are enabled
+// on the processor. together with the documented ones they cover every op code
+pub(crate) const NMOS_UNDOCUMENTED_OP_CODES: [OpCode; 105] = [
+    // read-modify-write combined with an accumulator operation
+    op_code(0x07, Instruction::SLO, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x17, Instruction::SLO, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x0F, Instruction::SLO, AddressModeKind::Absolute, 3, 6),
+    op_code(0x1F, Instruction::SLO, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x1B, Instruction::SLO, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0x03, Instruction::SLO, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0x13, Instruction::SLO, AddressModeKind::PostIndexedIndirectY, 2, 8),
+    op_code(0x27, Instruction::RLA, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x37, Instruction::RLA, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x2F, Instruction::RLA, AddressModeKind::Absolute, 3, 6),
+    op_code(0x3F, Instruction::RLA, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x3B, Instruction::RLA, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0x23, Instruction::RLA, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0x33, Instruction::RLA, AddressModeKind::PostIndexedIndirectY, 2, 8),
+    op_code(0x47, Instruction::SRE, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x57, Instruction::SRE, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x4F, Instruction::SRE, AddressModeKind::Absolute, 3, 6),
+    op_code(0x5F, Instruction::SRE, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x5B, Instruction::SRE, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0x43, Instruction::SRE, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0x53, Instruction::SRE, AddressModeKind::PostIndexedIndirectY, 2, 8),
+    op_code(0x67, Instruction::RRA, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0x77, Instruction::RRA, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0x6F, Instruction::RRA, AddressModeKind::Absolute, 3, 6),
+    op_code(0x7F, Instruction::RRA, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0x7B, Instruction::RRA, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0x63, Instruction::RRA, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0x73, Instruction::RRA, AddressModeKind::PostIndexedIndirectY, 2, 8),
+    op_code(0xC7, Instruction::DCP, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xD7, Instruction::DCP, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0xCF, Instruction::DCP, AddressModeKind::Absolute, 3, 6),
+    op_code(0xDF, Instruction::DCP, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0xDB, Instruction::DCP, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0xC3, Instruction::DCP, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0xD3, Instruction::DCP, AddressModeKind::PostIndexedIndirectY, 2, 8),
+    op_code(0xE7, Instruction::ISC, AddressModeKind::ZeroPage, 2, 5),
+    op_code(0xF7, Instruction::ISC, AddressModeKind::ZeroPageX, 2, 6),
+    op_code(0xEF, Instruction::ISC, AddressModeKind::Absolute, 3, 6),
+    op_code(0xFF, Instruction::ISC, AddressModeKind::AbsoluteX, 3, 7),
+    op_code(0xFB, Instruction::ISC, AddressModeKind::AbsoluteY, 3, 7),
+    op_code(0xE3, Instruction::ISC, AddressModeKind::PreIndexedIndirectX, 2, 8),
+    op_code(0xF3, Instruction::ISC, AddressModeKind::PostIndexedIndirectY, 2, 8),
+
+    op_code(0x87, Instruction::SAX, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x97, Instruction::SAX, AddressModeKind::ZeroPageY, 2, 4),
+    op_code(0x8F, Instruction::SAX, AddressModeKind::Absolute, 3, 4),
+    op_code(0x83, Instruction::SAX, AddressModeKind::PreIndexedIndirectX, 2, 6),
+
+    op_code(0xA7, Instruction::LAX, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0xB7, Instruction::LAX, AddressModeKind::ZeroPageY, 2, 4),
+    op_code(0xAF, Instruction::LAX, AddressModeKind::Absolute, 3, 4),
+    op_code(0xBF, Instruction::LAX, AddressModeKind::AbsoluteY, 3, 4),
+    op_code(0xA3, Instruction::LAX, AddressModeKind::PreIndexedIndirectX, 2, 6),
+    op_code(0xB3, Instruction::LAX, AddressModeKind::PostIndexedIndirectY, 2, 5),
+
+    // immediate
+    op_code(0x0B, Instruction::ANC, AddressModeKind::Immediate, 2, 2),
+    op_code(0x2B, Instruction::ANC, AddressModeKind::Immediate, 2, 2),
+    op_code(0x4B, Instruction::ALR, AddressModeKind::Immediate, 2, 2),
+    op_code(0x6B, Instruction::ARR, AddressModeKind::Immediate, 2, 2),
+    op_code(0xCB, Instruction::SBX, AddressModeKind::Immediate, 2, 2),
+    op_code(0xEB, Instruction::SBC, AddressModeKind::Immediate, 2, 2),
+
+    op_code(0xBB, Instruction::LAS, AddressModeKind::AbsoluteY, 3, 4),
+
+    // NOPs that still read their operand
+    op_code(0x1A, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0x3A, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0x5A, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0x7A, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0xDA, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0xFA, Instruction::NOP, AddressModeKind::Implied, 1, 2),
+    op_code(0x80, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x82, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x89, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0xC2, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0xE2, Instruction::NOP, AddressModeKind::Immediate, 2, 2),
+    op_code(0x04, Instruction::NOP, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x44, Instruction::NOP, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x64, Instruction::NOP, AddressModeKind::ZeroPage, 2, 3),
+    op_code(0x14, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x34, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x54, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x74, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xD4, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0xF4, Instruction::NOP, AddressModeKind::ZeroPageX, 2, 4),
+    op_code(0x0C, Instruction::NOP, AddressModeKind::Absolute, 3, 4),
+    op_code(0x1C, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x3C, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x5C, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0x7C, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0xDC, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+    op_code(0xFC, Instruction::NOP, AddressModeKind::AbsoluteX, 3, 4),
+
+    // lock the processor up until reset
+    op_code(0x02, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x12, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x22, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x32, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x42, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x52, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x62, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x72, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0x92, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0xB2, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0xD2, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+    op_code(0xF2, Instruction::JAM, AddressModeKind::Implied, 1, 2),
+
+    // unstable, these depend on the individual chip
+    op_code(0x8B, Instruction::ANE, AddressModeKind::Immediate, 2, 2),
+    op_code(0xAB, Instruction::LXA, AddressModeKind::Immediate, 2, 2),
+    op_code(0x9F, Instruction::SHA, AddressModeKind::AbsoluteY, 3, 5),
+    op_code(0x93, Instruction::SHA, AddressModeKind::PostIndexedIndirectY, 2, 6),
+    op_code(0x9E, Instruction::SHX, AddressModeKind::AbsoluteY, 3, 5),
+    op_code(0x9C, Instruction::SHY, AddressModeKind::AbsoluteX, 3, 5),
+    op_code(0x9B, Instruction::TAS, AddressModeKind::AbsoluteY, 3, 5),
+];
+
+pub(crate) const NMOS_OP_CODES: [OpCode; 151] = DOCUMENTED_OP_CODES;
+const CMOS_DOCUMENTED_OP_CODES: [OpCode; merged_len(&DOCUMENTED_OP_CODES, &CMOS_CHANGES)] =
+    merge(&DOCUMENTED_OP_CODES, &CMOS_CHANGES);
+pub(crate) const CMOS_OP_CODES: [OpCode; 256] = merge(&CMOS_DOCUMENTED_OP_CODES, &CMOS_UNDEFINED_NOPS);
+
+// indexed by op code, built at compile time so an op code defined twice fails the build
+pub(crate) static NMOS_DECODE_TABLE: [Option<OpCode>; 256] = decode_table(&NMOS_OP_CODES);
+pub(crate) static CMOS_DECODE_TABLE: [Option<OpCode>; 256] = decode_table(&CMOS_OP_CODES);
+pub(crate) static NMOS_UNDOCUMENTED_DECODE_TABLE: [Option<OpCode>; 256] = decode_table(&NMOS_UNDOCUMENTED_OP_CODES);
+
+// neither overlapping the documented op codes nor leaving any out
+const _: () = assert!(merged_len(&NMOS_OP_CODES, &NMOS_UNDOCUMENTED_OP_CODES) == 256);
+const _: () = assert!(merged_len(&CMOS_DOCUMENTED_OP_CODES, &CMOS_UNDEFINED_NOPS) == 256);
+
+const fn decode_table(op_codes: &[OpCode]) -> [Option<OpCode>; 256] {
+    let mut table = [None; 256];
+
+    let mut i = 0;
+    while i < op_codes.len() {
+        let op_code = op_codes[i];
+        let index = op_code.execution_metrics.op_code as usize;
+
+        if table[index].is_some() {
+            panic!("op code is defined more than once");
+        }
+
+        table[index] = Some(op_code);
+        i += 1;
+    }
+
+    table
+}
+
+const fn position(op_codes: &[OpCode], op_code: u8) -> Option<usize> {
+    let mut i = 0;
+    while i < op_codes.len() {
+        if op_codes[i].execution_metrics.op_code == op_code {
+            return Some(i);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+const fn merged_len(base: &[OpCode], changes: &[OpCode]) -> usize {
+    let mut len = base.len();
+
+    let mut i = 0;
+    while i < changes.len() {
+        if position(base, changes[i].execution_metrics.op_code).is_none() {
+            len += 1;
+        }
+        i += 1;
+    }
+
+    len
+}
+
+// replaces the rows of base that changes redefines, keeping their place, and
+// appends the rest so the base rows stay first
+const fn merge<const N: usize>(base: &[OpCode], changes: &[OpCode]) -> [OpCode; N] {
+    let mut merged = [base[0]; N];
+
+    let mut i = 0;
+    while i < base.len() {
+        merged[i] = base[i];
+        i += 1;
+    }
+
+    let mut len = base.len();
+    let mut i = 0;
+    while i < changes.len() {
+        match position(base, changes[i].execution_metrics.op_code) {
+            Some(index) => merged[index] = changes[i],
+            None => {
+                merged[len] = changes[i];
+                len += 1;
+            }
+        }
+        i += 1;
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::address::AddressModeKind;
+    use crate::processor::cmos::Cmos;
+    use crate::processor::nmos::Nmos;
+    use crate::processor::variant::Variant;
+    use crate::processor::Instruction;
+
+    #[test]
+    fn test_decode() {
+        let op_code = Cmos::decode(0x6d).unwrap();
+        assert_eq!(op_code.instruction, Instruction::ADC);
+        assert_eq!(op_code.address_mode, AddressModeKind::Absolute);
+        assert_eq!(op_code.execution_metrics.bytes, 3);
+        assert_eq!(op_code.execution_metrics.cycles, 4);
+
+        let op_code = Cmos::decode(0x0a).unwrap();
+        assert_eq!(op_code.instruction, Instruction::ASL);
+        assert_eq!(op_code.address_mode, AddressModeKind::Accumulator);
+
+        let op_code = Cmos::decode(0x1e).unwrap();
+        assert_eq!(op_code.instruction, Instruction::ASL);
+        assert_eq!(op_code.address_mode, AddressModeKind::AbsoluteX);
+        assert_eq!(op_code.execution_metrics.cycles, 6);
+        assert_eq!(Nmos::decode(0x1e).unwrap().execution_metrics.cycles, 7);
+        assert_eq!(Cmos::decode(0xfe).unwrap().execution_metrics.cycles, 7);
+
+        assert!(Nmos::decode(0x02).is_none());
+        assert_eq!((0..=255u8).filter(|op_code| Nmos::decode(*op_code).is_some()).count(), 151);
+    }
+
+    #[test]
+    fn test_variant_changes() {
+        assert_eq!(Nmos::decode(0x6c).unwrap().execution_metrics.cycles, 5);
+        assert_eq!(Cmos::decode(0x6c).unwrap().execution_metrics.cycles, 6);
+
+        // a changed row keeps its place, additions are appended
+        assert_eq!(Cmos::op_codes()[..151].iter().filter(|op_code| op_code.execution_metrics.op_code == 0x6c).count(), 1);
+        assert_eq!(Cmos::op_codes().len(), 256);
+
+        // the 65C02 additions don't exist on the NMOS part
+        let op_code = Cmos::decode(0xb2).unwrap();
+        assert_eq!(op_code.instruction, Instruction::LDA);
+        assert_eq!(op_code.address_mode, AddressModeKind::ZeroPageIndirect);
+        assert!(Nmos::decode(0xb2).is_none());
+        assert!(Nmos::decode(0x80).is_none());
+    }
+
+    fn assert_decode_agrees_with_execution_metrics<V: Variant>() {
+        for op_code in V::op_codes().iter() {
+            let decoded = V::decode(op_code.execution_metrics.op_code).unwrap();
+            assert_eq!(decoded.instruction, op_code.instruction);
+            assert_eq!(decoded.address_mode, op_code.address_mode);
+
+            // the NOPs share instruction and mode, looking up metrics finds the documented one
+            let first = V::op_codes()
+                .iter()
+                .find(|other| other.instruction == op_code.instruction && other.address_mode == op_code.address_mode)
+                .unwrap();
+
+            let execution_metrics = V::execution_metrics(&op_code.instruction, &op_code.address_mode).unwrap();
+            assert_eq!(execution_metrics.op_code, first.execution_metrics.op_code);
+            assert_eq!(execution_metrics.bytes, first.execution_metrics.bytes);
+            assert_eq!(execution_metrics.cycles, first.execution_metrics.cycles);
+        }
+    }
+
+    #[test]
+    fn test_cmos_undefined_nops() {
+        for op_code in 0..=255u8 {
+            assert!(Cmos::decode(op_code).is_some());
+        }
+
+        let op_code = Cmos::decode(0x5c).unwrap();
+        assert_eq!(op_code.instruction, Instruction::NOP);
+        assert_eq!(op_code.execution_metrics.bytes, 3);
+        assert_eq!(op_code.execution_metrics.cycles, 8);
+
+        let op_code = Cmos::decode(0xf3).unwrap();
+        assert_eq!(op_code.instruction, Instruction::NOP);
+        assert_eq!(op_code.execution_metrics.bytes, 1);
+        assert_eq!(op_code.execution_metrics.cycles, 1);
+
+        let execution_metrics = Cmos::execution_metrics(&Instruction::NOP, &AddressModeKind::Implied).unwrap();
+        assert_eq!(execution_metrics.op_code, 0xea);
+    }
+
+    #[test]
+    fn test_nmos_undocumented() {
+        // every op code is either documented or undocumented on the NMOS part
+        for op_code in 0..=255u8 {
+            assert!(Nmos::decode(op_code).is_some() != Nmos::decode_undocumented(op_code).is_some());
+        }
+
+        let op_code = Nmos::decode_undocumented(0xb3).unwrap();
+        assert_eq!(op_code.instruction, Instruction::LAX);
+        assert_eq!(op_code.address_mode, AddressModeKind::PostIndexedIndirectY);
+        assert!(Cmos::decode_undocumented(0xb3).is_none());
+
+        // the documented row wins when looking up metrics
+        let execution_metrics = Nmos::execution_metrics(&Instruction::SBC, &AddressModeKind::Immediate).unwrap();
+        assert_eq!(execution_metrics.op_code, 0xe9);
+    }
+
+    #[test]
+    fn test_decode_agrees_with_execution_metrics() {
+        assert_decode_agrees_with_execution_metrics::<Nmos>();
+        assert_decode_agrees_with_execution_metrics::<Cmos>();
+    }
+}
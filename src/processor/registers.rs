@@ -0,0 +1,13 @@
+use crate::processor::status::Status;
+use crate::processor::{Register16, Register8};
+
+// a copy of the programmer visible registers, taken and restored as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Registers {
+    pub program_counter: Register16,
+    pub accumulator: Register8,
+    pub x: Register8,
+    pub y: Register8,
+    pub stack_pointer: Register8,
+    pub status: Status,
+}
@@ -10,10 +10,18 @@ pub const FLAG_ZERO: u8 = 1;
 pub const FLAG_CARRY: u8 = 0;
 
 
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Status(pub(crate) Register8);
 
 impl Status {
+    pub fn from_bits(value: Register8) -> Status {
+        Status(value)
+    }
+
+    pub fn bits(&self) -> Register8 {
+        self.0
+    }
+
     #[inline(always)]
     pub fn get_bit(&self, n: u8) -> bool {
         self.get_bit_u8(n) == 1
@@ -26,7 +34,7 @@ impl Status {
 
     #[inline(always)]
     pub fn clear_bit(&mut self, n: u8) {
-        self.0 = self.0 & !(1u8 << n);
+        self.0 &= !(1u8 << n);
     }
 
     #[inline(always)]
@@ -34,30 +42,161 @@ impl Status {
         self.set_bit(n, true)
     }
 
-    // must be cleared first to work
+    // assigns the bit, whatever it held before
     #[inline(always)]
     pub fn set_bit(&mut self, n: u8, value: bool) {
-        self.0 = self.0 | ((value as u8) << n);
+        self.0 = (self.0 & !(1u8 << n)) | ((value as u8) << n);
+    }
+
+    pub fn carry(&self) -> bool {
+        self.get_bit(FLAG_CARRY)
+    }
+
+    pub fn set_carry(&mut self, value: bool) {
+        self.set_bit(FLAG_CARRY, value)
+    }
+
+    pub fn zero(&self) -> bool {
+        self.get_bit(FLAG_ZERO)
+    }
+
+    pub fn set_zero(&mut self, value: bool) {
+        self.set_bit(FLAG_ZERO, value)
+    }
+
+    pub fn interrupt_disable(&self) -> bool {
+        self.get_bit(FLAG_INTERRUPT_DISABLE)
+    }
+
+    pub fn set_interrupt_disable(&mut self, value: bool) {
+        self.set_bit(FLAG_INTERRUPT_DISABLE, value)
+    }
+
+    pub fn decimal(&self) -> bool {
+        self.get_bit(FLAG_DECIMAL)
+    }
+
+    pub fn set_decimal(&mut self, value: bool) {
+        self.set_bit(FLAG_DECIMAL, value)
+    }
+
+    // the register itself has no break bit, so this is only ever set on a status
+    // built from a pushed copy
+    pub fn break_flag(&self) -> bool {
+        self.get_bit(FLAG_BREAK)
+    }
+
+    pub fn set_break_flag(&mut self, value: bool) {
+        self.set_bit(FLAG_BREAK, value)
+    }
+
+    pub fn overflow(&self) -> bool {
+        self.get_bit(FLAG_OVERFLOW)
+    }
+
+    pub fn set_overflow(&mut self, value: bool) {
+        self.set_bit(FLAG_OVERFLOW, value)
+    }
+
+    pub fn negative(&self) -> bool {
+        self.get_bit(FLAG_NEGATIVE)
+    }
+
+    pub fn set_negative(&mut self, value: bool) {
+        self.set_bit(FLAG_NEGATIVE, value)
+    }
+
+    // the copy pushed to the stack. bit 5 always reads as set and the break bit is
+    // set for PHP and BRK, clear for IRQ and NMI
+    pub fn pushed(&self, break_flag: bool) -> Register8 {
+        let mut status = Status(self.0);
+        status.set_bit(FLAG_UNUSED_5, true);
+        status.set_break_flag(break_flag);
+        status.0
+    }
+
+    // the register as pulled back by PLP and RTI, bits 4 and 5 are dropped
+    pub fn pulled(value: Register8) -> Status {
+        Status(value & !((1 << FLAG_BREAK) | (1 << FLAG_UNUSED_5)))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::processor::status::{Status, FLAG_NEGATIVE, FLAG_OVERFLOW};
+    use crate::processor::status::{Status, FLAG_CARRY, FLAG_NEGATIVE, FLAG_OVERFLOW};
 
     #[test]
     fn test_status() {
         let mut status = Status::default();
-        assert_eq!(status.get_bit(FLAG_NEGATIVE), false);
+        assert!(!status.get_bit(FLAG_NEGATIVE));
 
         status.enable_bit(FLAG_NEGATIVE);
-        assert_eq!(status.get_bit(FLAG_NEGATIVE), true);
+        assert!(status.get_bit(FLAG_NEGATIVE));
         assert_eq!(status.0, 128);
 
         status.enable_bit(FLAG_OVERFLOW);
         status.clear_bit(FLAG_NEGATIVE);
-        assert_eq!(status.get_bit(FLAG_NEGATIVE), false);
-        assert_eq!(status.get_bit(FLAG_OVERFLOW), true);
+        assert!(!status.get_bit(FLAG_NEGATIVE));
+        assert!(status.get_bit(FLAG_OVERFLOW));
         assert_eq!(status.0, 64);
     }
+
+    #[test]
+    fn test_set_bit_assigns() {
+        let mut status = Status::default();
+
+        status.set_bit(FLAG_CARRY, true);
+        assert!(status.get_bit(FLAG_CARRY));
+
+        status.set_bit(FLAG_CARRY, false);
+        assert!(!status.get_bit(FLAG_CARRY));
+        assert_eq!(status.0, 0);
+
+        status.set_bit(FLAG_CARRY, true);
+        status.set_bit(FLAG_CARRY, true);
+        assert_eq!(status.0, 1);
+    }
+
+    #[test]
+    fn test_named_flags() {
+        let mut status = Status::default();
+
+        status.set_carry(true);
+        status.set_zero(true);
+        status.set_interrupt_disable(true);
+        status.set_decimal(true);
+        status.set_overflow(true);
+        status.set_negative(true);
+        assert_eq!(status.0, 0b1100_1111);
+        assert!(status.carry() && status.zero() && status.interrupt_disable());
+        assert!(status.decimal() && status.overflow() && status.negative());
+        assert!(!status.break_flag());
+
+        status.set_zero(false);
+        status.set_decimal(false);
+        assert_eq!(status.0, 0b1100_0101);
+        assert!(!status.zero());
+        assert!(!status.decimal());
+    }
+
+    #[test]
+    fn test_pushed_and_pulled() {
+        let mut status = Status::default();
+        status.set_carry(true);
+        status.set_negative(true);
+
+        // PHP and BRK
+        assert_eq!(status.pushed(true), 0b1011_0001);
+        // IRQ and NMI
+        assert_eq!(status.pushed(false), 0b1010_0001);
+
+        // a stray break bit in the register doesn't leak into an interrupt push
+        status.set_break_flag(true);
+        assert_eq!(status.pushed(false), 0b1010_0001);
+
+        let pulled = Status::pulled(0xff);
+        assert_eq!(pulled.0, 0b1100_1111);
+        assert!(!pulled.break_flag());
+        assert_eq!(Status::pulled(status.pushed(true)).0, 0b1000_0001);
+    }
 }
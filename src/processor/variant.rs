@@ -0,0 +1,63 @@
+use crate::memory::address::AddressModeKind;
+use crate::processor::decimal::DecimalResult;
+use crate::processor::op_codes::OpCode;
+use crate::processor::{ExecutionMetrics, Instruction, Value};
+
+// what differs between the chips sharing the processor core
+pub trait Variant {
+    // cycles added to ADC and SBC in decimal mode
+    const DECIMAL_CYCLES: u8;
+
+    // whether reset, BRK and the interrupts clear the decimal flag
+    const INTERRUPT_CLEARS_DECIMAL: bool;
+
+    // whether JMP ($xxFF) reads the high byte of the target from $xx00
+    const INDIRECT_PAGE_WRAP: bool;
+
+    // whether read-modify-write instructions write the unmodified value back
+    // before writing the result, rather than reading it a second time
+    const READ_MODIFY_WRITE_DOUBLE_WRITE: bool;
+
+    // whether the cycle spent carrying into the high byte of an address reads from the
+    // address without the carry, rather than the last byte of the instruction again
+    const PAGE_CROSS_READS_INVALID_ADDRESS: bool;
+
+    // whether ASL, LSR, ROL and ROR in abs,X only spend the cycle fixing up the
+    // high byte of the address when indexing crosses a page
+    const SHIFT_INDEXED_PAGE_PENALTY: bool;
+
+    // every op code the chip decodes, the documented ones first so looking up
+    // metrics by instruction finds those
+    fn op_codes() -> &'static [OpCode];
+
+    fn decode(op_code: u8) -> Option<&'static OpCode>;
+
+    // op codes outside the documented set, only decoded once enabled on the processor
+    fn undocumented_op_codes() -> &'static [OpCode] {
+        &[]
+    }
+
+    fn decode_undocumented(_op_code: u8) -> Option<&'static OpCode> {
+        None
+    }
+
+    fn execution_metrics(instruction: &Instruction, address_mode: &AddressModeKind) -> Option<ExecutionMetrics> {
+        find_metrics(Self::op_codes(), instruction, address_mode)
+    }
+
+    // like decode_undocumented, the processor only looks these up once enabled
+    fn undocumented_execution_metrics(instruction: &Instruction, address_mode: &AddressModeKind) -> Option<ExecutionMetrics> {
+        find_metrics(Self::undocumented_op_codes(), instruction, address_mode)
+    }
+
+    fn adc_decimal(a: Value, b: Value, carry: bool) -> DecimalResult;
+
+    fn sbc_decimal(a: Value, b: Value, carry: bool) -> DecimalResult;
+}
+
+fn find_metrics(op_codes: &[OpCode], instruction: &Instruction, address_mode: &AddressModeKind) -> Option<ExecutionMetrics> {
+    op_codes
+        .iter()
+        .find(|op_code| op_code.instruction == *instruction && op_code.address_mode == *address_mode)
+        .map(|op_code| op_code.execution_metrics)
+}